@@ -1,15 +1,23 @@
-use crate::workspace::{FileContent, Modification, PathContent, PathInfo, ProjectInfo, WorkspaceInfo};
-use crate::wrapper::AccountInfo;
-use crate::{workspace, wrapper};
+use crate::audio::{AudioClip, AudioPlayer};
+use crate::diff::{ChangeDiff, DiffLine, DiffTag, FileDiff, ImageDiff, ImageDiffMode};
+use crate::history::History;
+use crate::ignore::IgnoreRules;
+use crate::viewer::{AnimationSection, FontMetadata, ImagePreview, TextPreview, TextureAnimation};
+use crate::workspace::{ChangeKind, EntryMode, FileContent, Modification, PathContent, PathInfo, PendingChange, ProjectInfo, RenameMode, SendProgress, TreeRow, WorkspaceInfo};
+use crate::wrapper::{AccountInfo, CollaboratorInfo, CommitIdentity, Settings, TreePart};
+use crate::{audio, diff, viewer, workspace, wrapper};
 use iced::alignment::{Horizontal, Vertical};
 use iced::theme::Palette;
 use iced::widget::image::{FilterMethod, Viewer};
-use iced::widget::{button, image, markdown, scrollable, svg, text, Button, Checkbox, Column, Container, Image, Row, Scrollable, Svg, Text, TextInput};
+use iced::widget::{button, container, image, markdown, mouse_area, rich_text, scrollable, svg, text, text_editor, tooltip, Button, Checkbox, Column, Container, Image, PickList, ProgressBar, Row, Scrollable, Slider, Space, Stack, Svg, Text, TextEditor, TextInput, Tooltip};
 use iced::window::icon;
-use iced::{widget, window, Alignment, Background, Border, Color, Degrees, Element, Length, Padding, Radians, Renderer, Rotation, Shadow, Subscription, Task, Theme};
+use iced::{event, font, keyboard, mouse, widget, window, Alignment, Background, Border, Color, ContentFit, Degrees, Element, Font, Length, Padding, Radians, Renderer, Rotation, Shadow, Subscription, Task, Theme};
 use octocrab::Octocrab;
 use reqwest::Url;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 // Illusionna Icons
 const ICON: &[u8] = include_bytes!("../resources/icon.png").as_slice();
@@ -23,6 +31,33 @@ const REMOVE: &[u8] = include_bytes!("../resources/remove.svg").as_slice();
 const FOLDER: &[u8] = include_bytes!("../resources/folder.svg").as_slice();
 const COLLAPSE: &[u8] = include_bytes!("../resources/collapse.svg").as_slice();
 const EXPAND: &[u8] = include_bytes!("../resources/expand.svg").as_slice();
+const SYMLINK: &[u8] = include_bytes!("../resources/symlink.svg").as_slice();
+const EXECUTABLE: &[u8] = include_bytes!("../resources/executable.svg").as_slice();
+const SUBMODULE: &[u8] = include_bytes!("../resources/submodule.svg").as_slice();
+const UNDO: &[u8] = include_bytes!("../resources/undo.svg").as_slice();
+const REDO: &[u8] = include_bytes!("../resources/redo.svg").as_slice();
+const IMPORT: &[u8] = include_bytes!("../resources/import.svg").as_slice();
+const NEW_FOLDER: &[u8] = include_bytes!("../resources/new-folder.svg").as_slice();
+const NEW_FILE: &[u8] = include_bytes!("../resources/new-file.svg").as_slice();
+const DUPLICATE: &[u8] = include_bytes!("../resources/duplicate.svg").as_slice();
+const GRIP: &[u8] = include_bytes!("../resources/grip.svg").as_slice();
+const DIFF: &[u8] = include_bytes!("../resources/diff.svg").as_slice();
+
+/// Rows of a diff displayed at most, as each of them is a widget of its own.
+const MAX_DIFF_ROWS: usize = 2000;
+
+/// Name of the font embedded in Illusionna and used for its interface.
+pub const APP_FONT: &str = "Inter 24pt";
+
+/// Text shown by the font viewer until it is changed, along with the sizes it is shown at.
+const FONT_SAMPLE: &str = "The quick brown fox jumps over the lazy dog";
+const FONT_SPECIMEN_SIZES: [f32; 5] = [12.0, 18.0, 24.0, 36.0, 48.0];
+
+/// Rows of the workspace tree have a fixed height, so that only the visible ones get built.
+const TREE_ROW_HEIGHT: f32 = 34.0;
+const TREE_HEADER_HEIGHT: f32 = 42.0;
+const TREE_PADDING: f32 = 10.0;
+const TREE_OVERSCAN: usize = 8;
 
 #[derive(Debug, Clone)]
 enum CrabState {
@@ -36,7 +71,36 @@ pub enum Display {
     ProjectSelection,
     WorkspaceSelection,
     WorkspaceCreation,
-    WorkspaceContent
+    WorkspaceContent,
+    Settings
+}
+
+/// A folder or a file being named before its creation inside a directory of the workspace.
+#[derive(Debug, Clone)]
+pub struct Creation {
+    directory: String,
+    folder: bool,
+    input: String
+}
+
+/// A pending task whose result is awaited, keeping the loading animation running.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Loading {
+    Projects,
+    Workspaces,
+    WorkspaceContent,
+    Directory(String),
+    File,
+    Diff,
+    Sending,
+    /// The .gitignore files found while loading a directory, the root being an empty path.
+    Gitignores(String)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMode {
+    Unified,
+    SideBySide
 }
 
 #[derive(Debug, Clone)]
@@ -46,9 +110,11 @@ pub enum ReferenceValidation {
     Unspecified
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct IllusionnaApp {
     rotator: u16,
+    loading: HashMap<Loading, Option<f32>>,
+    displayed_progress: f32,
     crab: CrabState,
     display: Display,
     projects: Option<Vec<ProjectInfo>>,
@@ -64,18 +130,72 @@ pub struct IllusionnaApp {
     selected_workspace: Option<WorkspaceInfo>,
     workspace_content: Option<BTreeMap<String, PathInfo>>,
     workspace_content_filter: String,
-    collapsed_directories: Vec<String>,
+    workspace_filter_matches: Option<HashSet<String>>,
+    workspace_rows: Vec<TreeRow>,
+    workspace_scroll: (f32, f32),
+    collapsed_directories: HashSet<String>,
     viewed_file_path: Option<String>,
     viewed_file_name: Option<String>,
     viewed_file_content: Option<Vec<u8>>,
+    viewed_text: Option<TextPreview<Interaction>>,
+    viewed_image: Option<ImagePreview>,
+    viewed_audio: Option<AudioClip>,
+    audio_player: Option<AudioPlayer>,
+    viewed_font: Option<FontMetadata>,
+    viewed_font_face: Option<Font>,
+    viewed_font_conflict: bool,
+    font_family_names: HashSet<&'static str>,
+    loaded_fonts: HashMap<Font, Option<u64>>,
+    font_sample: String,
+    upstream_dimensions: Option<(u32, u32)>,
+    animation_section: Option<AnimationSection>,
+    texture_animation: Option<TextureAnimation>,
+    animation_paused: bool,
+    animation_offset: u64,
+    animation_start: Instant,
+    animation_elapsed: Duration,
+    file_editor: Option<text_editor::Content>,
+    diff: Option<FileDiff>,
+    diff_mode: DiffMode,
+    image_diff: Option<ImageDiff>,
+    image_diff_mode: ImageDiffMode,
+    image_diff_position: f32,
     refactors: HashMap<String, String>,
+    creation: Option<Creation>,
+    duplicates: HashMap<String, String>,
     modification: Modification,
+    sent_modification: Option<Modification>,
     modification_name: String,
+    commit_description: text_editor::Content,
+    co_authors: Vec<CollaboratorInfo>,
+    collaborators: Vec<CollaboratorInfo>,
+    settings: Settings,
+    settings_origin: Display,
+    history: History,
+    show_pending_changes: bool,
+    import_preview: Option<BTreeMap<String, Vec<u8>>>,
+    drop_target: String,
+    dropped_files: Option<(String, Vec<PathBuf>)>,
+    tree_hovered: bool,
+    dragged: Option<String>,
+    modifiers: keyboard::Modifiers,
+    selection: BTreeSet<String>,
+    selection_anchor: Option<String>,
+    bulk_destination: String,
+    rename_mode: RenameMode,
+    rename_pattern: String,
+    rename_replacement: String,
+    gitignores: BTreeMap<String, String>,
+    notice: Option<String>,
+    ignore_patterns: text_editor::Content,
+    files_hovered: bool,
+    staged_changes: BTreeSet<String>
 }
 
 #[derive(Debug, Clone)]
 pub enum Interaction {
     Tick,
+    AnimationFrame(Instant),
     StartDeviceFlow,
     CompleteDeviceFlow(Octocrab),
     ReceiveProjectInfos(Vec<ProjectInfo>),
@@ -100,18 +220,92 @@ pub enum Interaction {
     OpenWorkspace(String),
     ReceiveWorkspaceContent(BTreeMap<String, PathInfo>, Modification, bool),
     FilterWorkspaceContent(String),
+    ScrollWorkspaceContent(scrollable::Viewport),
     CollapseDirectory(String),
     ExpandDirectory(String),
+    ReceiveDirectoryContent(String, Vec<TreePart>),
     ViewFile(String, String),
-    ProcessViewingContent(Vec<u8>),
+    ProcessViewingContent(Result<Vec<u8>, String>),
     SelectFiles(bool, String),
     SetFiles(HashMap<String, Vec<u8>>),
+    ReplaceFiles(HashMap<String, Vec<u8>>),
+    SelectFolder(String),
+    PreviewImport(BTreeMap<String, Vec<u8>>),
+    HoverDropTarget(String),
+    HoverTree(bool),
+    ModifiersChanged(keyboard::Modifiers),
+    ClickRow(String, Option<String>),
+    SelectMatches,
+    ClearSelection,
+    DeleteSelection,
+    BulkDestinationInput(String),
+    MoveSelection,
+    RenameModeSelected(RenameMode),
+    RenamePatternInput(String),
+    RenameReplacementInput(String),
+    RenameSelection,
+    StartDrag(String),
+    FinishDrag,
+    ReceiveGitignores(String, Vec<(String, String)>),
+    DismissNotice,
+    FilesHovered,
+    FilesHoveredLeft,
+    FileDropped(PathBuf),
+    ImportDroppedFiles,
+    ApplyImport,
+    CancelImport,
     RefactorFiles(String),
     RefactorInput(String, String),
     PushRefactor(String, String),
     EraseFiles(String),
+    DuplicateFiles(String),
+    DuplicateInput(String, String),
+    PushDuplicate(String),
+    CreateEntry(String, bool),
+    CreationInput(String),
+    PushCreation,
+    TogglePendingChanges,
+    RevertChange(String),
+    StageChange(String, bool),
+    StageAllChanges(bool),
+    Undo,
+    Redo,
     ModificationNameInput(String),
+    CommitDescriptionAction(text_editor::Action),
+    ReceiveCollaborators(Vec<CollaboratorInfo>),
+    AddCoAuthor(CollaboratorInfo),
+    RemoveCoAuthor(String),
+    OpenSettings,
+    CloseSettings,
+    AuthorNameInput(String),
+    AuthorEmailInput(String),
+    ToggleVersionTrailer(bool),
+    CustomTrailerInput(String),
+    IgnorePatternsAction(text_editor::Action),
+    ViewDiff(String),
+    ReceiveDiff(String, Result<ChangeDiff, String>),
+    ReceiveUpstreamImage(String, Vec<u8>),
+    ReceiveMcmeta(String, Vec<u8>),
+    ReceiveImage(String, Option<ImagePreview>),
+    ReceiveAudio(String, Option<Result<AudioClip, String>>),
+    ToggleAudio,
+    SeekAudio(f32),
+    ReceiveFont(String, u64, bool),
+    UpdateFontSample(String),
+    ToggleAnimation,
+    StepAnimation(bool),
+    SetDiffMode(DiffMode),
+    SetImageDiffMode(ImageDiffMode),
+    ImageDiffPosition(f32),
+    CloseDiff,
+    EditFile,
+    FileEditorAction(text_editor::Action),
+    SaveFile,
+    CancelEdit,
+    SaveSettings,
     SendChanges,
+    SendProgress(SendProgress),
+    ReloadWorkspace,
     ResetChanges
 }
 
@@ -143,6 +337,114 @@ pub fn small_button(theme: &Theme, status: button::Status) -> button::Style {
     button::Style { border: Border::default().rounded(3), ..large_button(theme, status) }
 }
 
+pub fn change_color(kind: ChangeKind) -> Color {
+    match kind {
+        ChangeKind::Added => Color::from_rgb8(0, 170, 0),
+        ChangeKind::Replaced => Color::from_rgb8(230, 150, 0),
+        ChangeKind::Renamed => Color::from_rgb8(60, 140, 255),
+        ChangeKind::Deleted => Color::from_rgb8(220, 0, 0)
+    }
+}
+
+pub fn change_marker(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "A",
+        ChangeKind::Replaced => "M",
+        ChangeKind::Renamed => "R",
+        ChangeKind::Deleted => "D"
+    }
+}
+
+/// Displays a line of a diff after its gutter, with a background for removed and added lines
+/// and a stronger color for the words that changed inside them.
+fn diff_line<'a>(line: Option<&'a DiffLine>, gutter: String) -> Element<'a, Interaction, Theme, Renderer> {
+    let tag = line.map(|line| line.tag);
+    let (background, strong) = match tag {
+        Some(DiffTag::Removed) => (Some(Color::from_rgba8(255, 0, 0, 0.15)), Color::from_rgb8(255, 120, 120)),
+        Some(DiffTag::Added) => (Some(Color::from_rgba8(0, 255, 0, 0.12)), Color::from_rgb8(120, 230, 120)),
+        _ => (None, Color::WHITE)
+    };
+    let spans = line.map(|line| line.segments.iter()
+        .map(|(segment, changed)| text::Span::new(segment.as_str()).color_maybe(changed.then_some(strong)))
+        .collect::<Vec<text::Span<'a, Interaction>>>()
+    ).unwrap_or_default();
+    Container::new(
+        Row::new()
+            .push(Text::new(gutter).font(Font::MONOSPACE).size(12).color(Color::from_rgb8(128, 128, 128)))
+            .push(rich_text(spans).font(Font::MONOSPACE).size(12).wrapping(text::Wrapping::None))
+            .spacing(8)
+    )
+        .width(Length::Fill)
+        .style(move |_| container::Style::default().background(background.unwrap_or(Color::TRANSPARENT)))
+        .into()
+}
+
+/// Highlights the row of the directory files would be dropped into, and the selected rows.
+pub fn tree_row(theme: &Theme, highlighted: bool, selected: bool) -> container::Style {
+    if highlighted {
+        container::Style::default()
+            .background(theme.extended_palette().primary.weak.color)
+            .border(Border::default().rounded(5))
+    }
+    else if selected {
+        container::Style::default()
+            .background(theme.extended_palette().background.strong.color)
+            .border(Border::default().rounded(5))
+    }
+    else {
+        container::Style::default()
+    }
+}
+
+pub fn format_size(size: u64) -> String {
+    let units = ["B", "KB", "MB", "GB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024f64 && unit < units.len() - 1 {
+        value /= 1024f64;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} {}", size, units[0]) } else { format!("{:.1} {}", value, units[unit]) }
+}
+
+/// Identifies the content of a file, to tell apart fonts loaded under the same name.
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Maps the weight class of a font to the closest weight known by the text renderer.
+pub fn font_weight(weight: u16) -> font::Weight {
+    match weight {
+        0..=149 => font::Weight::Thin,
+        150..=249 => font::Weight::ExtraLight,
+        250..=349 => font::Weight::Light,
+        350..=449 => font::Weight::Normal,
+        450..=549 => font::Weight::Medium,
+        550..=649 => font::Weight::Semibold,
+        650..=749 => font::Weight::Bold,
+        750..=849 => font::Weight::ExtraBold,
+        _ => font::Weight::Black
+    }
+}
+
+/// Formats a duration as minutes and seconds, with tenths of seconds for short sounds.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f32();
+    if seconds < 10.0 { format!("0:{:04.1}", seconds) } else { format!("{}:{:02}", duration.as_secs() / 60, duration.as_secs() % 60) }
+}
+
+/// Key bindings of the text editors, which swallow the history shortcuts while they are focused
+/// so that undoing typed text does not also undo an edit of the workspace.
+fn editor_binding(key_press: text_editor::KeyPress) -> Option<text_editor::Binding<Interaction>> {
+    let history = matches!(key_press.key.as_ref(), keyboard::Key::Character("z" | "Z" | "y" | "Y")) && key_press.modifiers.command();
+    if history && key_press.status == text_editor::Status::Focused {
+        return Some(text_editor::Binding::Sequence(vec![]));
+    }
+    text_editor::Binding::from_key_press(key_press)
+}
+
 pub fn default_svg(theme: &Theme, _: svg::Status) -> svg::Style {
     svg::Style {
         color: if theme.extended_palette().is_dark { Some(Color::WHITE) } else { Some(Color::BLACK) }
@@ -166,6 +468,8 @@ impl IllusionnaApp {
         (
             IllusionnaApp {
                 rotator: 0u16,
+                loading: HashMap::new(),
+                displayed_progress: 0.0,
                 crab: CrabState::Absent,
                 display: Display::GithubConnexion,
                 projects: None,
@@ -181,20 +485,172 @@ impl IllusionnaApp {
                 selected_workspace: None,
                 workspace_content: None,
                 workspace_content_filter: "".to_string(),
-                collapsed_directories: vec![],
+                workspace_filter_matches: None,
+                workspace_rows: vec![],
+                workspace_scroll: (0.0, 0.0),
+                collapsed_directories: HashSet::new(),
                 viewed_file_path: None,
                 viewed_file_name: None,
                 viewed_file_content: None,
+                viewed_text: None,
+                viewed_image: None,
+                viewed_audio: None,
+                audio_player: None,
+                viewed_font: None,
+                viewed_font_face: None,
+                viewed_font_conflict: false,
+                font_family_names: HashSet::from([APP_FONT]),
+                loaded_fonts: HashMap::from([(Font::with_name(APP_FONT), None)]),
+                font_sample: FONT_SAMPLE.to_string(),
+                upstream_dimensions: None,
+                animation_section: None,
+                texture_animation: None,
+                animation_paused: false,
+                animation_offset: 0,
+                animation_start: Instant::now(),
+                animation_elapsed: Duration::ZERO,
+                file_editor: None,
+                diff: None,
+                diff_mode: DiffMode::Unified,
+                image_diff: None,
+                image_diff_mode: ImageDiffMode::SideBySide,
+                image_diff_position: 0.5,
                 refactors: HashMap::new(),
+                creation: None,
+                duplicates: HashMap::new(),
                 modification: Modification::new(),
-                modification_name: "".to_string()
+                sent_modification: None,
+                modification_name: "".to_string(),
+                commit_description: text_editor::Content::new(),
+                co_authors: vec![],
+                collaborators: vec![],
+                settings: wrapper::get_stored_settings().unwrap_or_default(),
+                settings_origin: Display::GithubConnexion,
+                history: History::new(),
+                show_pending_changes: false,
+                import_preview: None,
+                drop_target: "".to_string(),
+                dropped_files: None,
+                tree_hovered: false,
+                dragged: None,
+                modifiers: keyboard::Modifiers::default(),
+                selection: BTreeSet::new(),
+                selection_anchor: None,
+                bulk_destination: "".to_string(),
+                rename_mode: RenameMode::Prefix,
+                rename_pattern: "".to_string(),
+                rename_replacement: "".to_string(),
+                gitignores: BTreeMap::new(),
+                notice: None,
+                ignore_patterns: text_editor::Content::new(),
+                files_hovered: false,
+                staged_changes: BTreeSet::new()
             },
             icon_task
         )
     }
 
+    pub fn subscription(&self) -> Subscription<Interaction> {
+        Subscription::batch([self.ticker(), self.animation(), self.shortcuts(), self.modifiers(), self.file_drops(), self.drags()])
+    }
+
+    /// Redraws every frame only while a task is loading, to animate its indicator.
     pub fn ticker(&self) -> Subscription<Interaction> {
-        window::frames().map(|_| Interaction::Tick)
+        if self.loading.is_empty() {
+            Subscription::none()
+        }
+        else {
+            window::frames().map(|_| Interaction::Tick)
+        }
+    }
+
+    /// Plays back the viewed image while it is animated, either by itself or by a .mcmeta file,
+    /// and follows the position of the viewed sound while it is playing.
+    pub fn animation(&self) -> Subscription<Interaction> {
+        let texture = self.texture_animation.is_some() && !self.animation_paused;
+        let audio = self.audio_player.as_ref().is_some_and(|player| player.is_playing());
+        if texture || audio || self.viewed_image.as_ref().is_some_and(|image| image.is_animated()) {
+            window::frames().map(Interaction::AnimationFrame)
+        }
+        else {
+            Subscription::none()
+        }
+    }
+
+    /// Files dragged from the file manager, which get dropped inside the directory whose row was
+    /// hovered last.
+    pub fn file_drops(&self) -> Subscription<Interaction> {
+        let events = event::listen_with(|event, _, _| match event {
+            iced::Event::Window(window::Event::FileHovered(_)) => Some(Interaction::FilesHovered),
+            iced::Event::Window(window::Event::FilesHoveredLeft) => Some(Interaction::FilesHoveredLeft),
+            iced::Event::Window(window::Event::FileDropped(path)) => Some(Interaction::FileDropped(path)),
+            _ => None
+        });
+        // Every path of a drop gets its own event, all of them before the next frame, so they are
+        // imported together once it is drawn.
+        match self.dropped_files {
+            Some(_) => Subscription::batch([events, window::frames().map(|_| Interaction::ImportDroppedFiles)]),
+            None => events
+        }
+    }
+
+    /// Keeps track of the keyboard modifiers, used to extend the selection of the tree.
+    pub fn modifiers(&self) -> Subscription<Interaction> {
+        event::listen_with(|event, _, _| match event {
+            iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => Some(Interaction::ModifiersChanged(modifiers)),
+            _ => None
+        })
+    }
+
+    /// Rows dragged by their handle get dropped wherever the mouse button is released.
+    pub fn drags(&self) -> Subscription<Interaction> {
+        if self.dragged.is_none() {
+            return Subscription::none();
+        }
+        event::listen_with(|event, _, _| match event {
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => Some(Interaction::FinishDrag),
+            _ => None
+        })
+    }
+
+    fn start_loading(&mut self, loading: Loading) {
+        self.loading.insert(loading, None);
+    }
+
+    fn finish_loading(&mut self, loading: &Loading) {
+        self.loading.remove(loading);
+    }
+
+    fn is_loading(&self, loading: &Loading) -> bool {
+        self.loading.contains_key(loading)
+    }
+
+    fn loading_icon(&self, size: f32) -> Image<image::Handle> {
+        Image::new(image::Handle::from_bytes(ICON))
+            .width(Length::Fixed(size))
+            .height(Length::Fixed(size))
+            .rotation(Rotation::Floating(Radians::from(Degrees(self.rotator as f32))))
+    }
+
+    pub fn shortcuts(&self) -> Subscription<Interaction> {
+        if self.file_editor.is_some() {
+            return keyboard::on_key_press(|key, modifiers| match key.as_ref() {
+                keyboard::Key::Character("s" | "S") if modifiers.command() => Some(Interaction::SaveFile),
+                keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Interaction::CancelEdit),
+                _ => None
+            });
+        }
+        keyboard::on_key_press(|key, modifiers| {
+            if !modifiers.command() {
+                return None;
+            }
+            match key.as_ref() {
+                keyboard::Key::Character("z" | "Z") if modifiers.shift() => Some(Interaction::Redo),
+                keyboard::Key::Character("z" | "Z") => Some(Interaction::Undo),
+                keyboard::Key::Character("y" | "Y") => Some(Interaction::Redo),
+                _ => None
+            }
+        })
     }
 
     pub fn get_crab(&self) -> &Octocrab {
@@ -204,6 +660,276 @@ impl IllusionnaApp {
         }
     }
 
+    /// Staged paths that are still pending, as reverting or undoing can drop staged changes.
+    fn staged_paths(&self, pending: &[PendingChange]) -> Vec<String> {
+        pending.iter()
+            .map(|change| change.path.clone())
+            .filter(|path| self.staged_changes.contains(path))
+            .collect()
+    }
+
+    /// Builds the commit message out of its summary, its description and its trailers.
+    fn commit_message(&self) -> String {
+        let mut message = self.modification_name.trim().to_string();
+        let description = self.commit_description.text();
+        if !description.trim().is_empty() {
+            message = format!("{}\n\n{}", message, description.trim_end());
+        }
+        let mut trailers = self.co_authors.iter()
+            .map(|collaborator| format!("Co-authored-by: {} <{}>", collaborator.login, wrapper::noreply_email(collaborator.id, &collaborator.login)))
+            .collect::<Vec<String>>();
+        if self.settings.record_version {
+            trailers.push(format!("Illusionna-Version: {}", env!("CARGO_PKG_VERSION")));
+        }
+        if !self.settings.custom_trailer.trim().is_empty() {
+            trailers.push(self.settings.custom_trailer.trim().to_string());
+        }
+        if !trailers.is_empty() {
+            message = format!("{}\n\n{}", message, trailers.join("\n"));
+        }
+        message
+    }
+
+    /// The commit author, taken from the settings if specified or from the GitHub profile. It is
+    /// None when the name or the email is missing, GitHub then recording the account itself.
+    fn commit_author(&self) -> Option<CommitIdentity> {
+        let profile = self.account.as_ref().map(|account| account.identity.clone());
+        let name = if !self.settings.author_name.is_empty() {
+            self.settings.author_name.clone()
+        } else {
+            profile.as_ref().map(|identity| identity.name.clone()).unwrap_or_default()
+        };
+        let email = if wrapper::is_valid_email(&self.settings.author_email) {
+            self.settings.author_email.clone()
+        } else {
+            profile.map(|identity| identity.email).unwrap_or_default()
+        };
+        (!name.is_empty() && !email.is_empty()).then_some(CommitIdentity { name, email })
+    }
+
+    /// Rebuilds the filter matches and the displayed rows, to be called whenever the workspace
+    /// content or the filter changes.
+    fn index_workspace_content(&mut self) {
+        self.workspace_filter_matches = match &self.workspace_content {
+            Some(content) if !self.workspace_content_filter.is_empty() => {
+                let mut matches = HashSet::new();
+                workspace::filter_content(content, &self.workspace_content_filter.to_lowercase(), &mut matches);
+                Some(matches)
+            }
+            _ => None
+        };
+        self.flatten_workspace_content();
+    }
+
+    /// Rebuilds the displayed rows alone, for when directories get collapsed or expanded.
+    fn flatten_workspace_content(&mut self) {
+        self.workspace_rows.clear();
+        if let Some(content) = &self.workspace_content {
+            workspace::flatten_content(content, &self.collapsed_directories, self.workspace_filter_matches.as_ref(), 0, &mut self.workspace_rows);
+        }
+    }
+
+    /// The ignore rules of the workspace: the built-in junk list, the patterns from the settings,
+    /// then the .gitignore files from the shallowest to the deepest one.
+    fn ignore_rules(&self) -> IgnoreRules {
+        let mut rules = IgnoreRules::new();
+        rules.add("", &self.settings.ignore_patterns);
+        let mut gitignores = self.gitignores.iter().collect::<Vec<(&String, &String)>>();
+        gitignores.sort_by_key(|(path, _)| path.matches("/").count());
+        for (path, contents) in gitignores {
+            let base = path.rsplit_once("/").map(|(base, _)| base).unwrap_or("");
+            match self.modification.view(path) {
+                Some(FileContent::Bytes(bytes)) => rules.add(base, &String::from_utf8_lossy(bytes)),
+                _ => rules.add(base, contents)
+            }
+        }
+        rules
+    }
+
+    /// Whether .gitignore files are still being fetched, in which case imports are refused as their
+    /// ignored files cannot be told apart yet.
+    fn ignore_rules_pending(&mut self) -> bool {
+        let pending = self.loading.keys().any(|loading| matches!(loading, Loading::Gitignores(_)));
+        if pending {
+            self.notice = Some("The .gitignore files of the workspace are still loading, please try again in a moment.".to_string());
+        }
+        pending
+    }
+
+    /// Drops the ignored files out of an import and reports them. Files that already exist in the
+    /// workspace are kept, as replacing them was explicitly asked for.
+    fn skip_ignored<C>(&mut self, files: C) -> C where C: IntoIterator<Item = (String, Vec<u8>)> + FromIterator<(String, Vec<u8>)> {
+        let rules = self.ignore_rules();
+        let mut skipped = vec![];
+        let kept = files.into_iter()
+            .filter(|(path, _)| {
+                let ignored = self.modification.mode(path).is_none() && rules.is_ignored(path);
+                if ignored {
+                    skipped.push(path.clone());
+                }
+                !ignored
+            })
+            .collect();
+        if !skipped.is_empty() {
+            skipped.sort();
+            let mut listed = skipped.iter().take(5).cloned().collect::<Vec<String>>().join(", ");
+            if skipped.len() > 5 {
+                listed = format!("{} and {} more", listed, skipped.len() - 5);
+            }
+            self.notice = Some(format!("Skipped {} ignored file(s): {}", skipped.len(), listed));
+        }
+        kept
+    }
+
+    /// Views the viewed file again after its pending content was changed by the history, dropping
+    /// any edit in progress, or closes it when it no longer exists.
+    fn reload_viewed_file(&mut self) -> Task<Interaction> {
+        let Some(path) = self.viewed_file_path.clone() else { return Task::none() };
+        let sha = self.workspace_content.as_ref()
+            .and_then(|content| workspace::find_content(content, &path))
+            .map(|info| info.sha.clone());
+        match sha {
+            Some(sha) => Task::done(Interaction::ViewFile(sha, path)),
+            None => {
+                self.viewed_file_path = None;
+                self.viewed_file_name = None;
+                self.viewed_file_content = None;
+                self.viewed_text = None;
+                self.viewed_image = None;
+                self.viewed_audio = None;
+                self.stop_audio();
+                self.viewed_font = None;
+                self.viewed_font_face = None;
+                self.viewed_font_conflict = false;
+                self.file_editor = None;
+                Task::none()
+            }
+        }
+    }
+
+    /// Fetches the .mcmeta file sitting next to a viewed file, either pending or upstream, which
+    /// makes the texture animated.
+    fn fetch_mcmeta(&self, path: &str) -> Task<Interaction> {
+        let mcmeta = format!("{}.mcmeta", path);
+        let path = path.to_string();
+        let sha = match self.modification.view(&mcmeta) {
+            Some(FileContent::Bytes(bytes)) => return Task::done(Interaction::ReceiveMcmeta(path, bytes.clone())),
+            Some(FileContent::Sha(sha)) => sha.clone(),
+            None => match self.workspace_content.as_ref().and_then(|content| workspace::find_content(content, &mcmeta)) {
+                Some(info) if matches!(info.content, PathContent::File(_)) => info.sha.clone(),
+                _ => return Task::none()
+            }
+        };
+        let crab = self.get_crab().clone();
+        let workspace = self.selected_workspace.clone().unwrap();
+        // An unreadable .mcmeta only leaves the texture unanimated.
+        Task::perform(workspace::get_file_content(crab, workspace, sha), move |bytes| Interaction::ReceiveMcmeta(path.clone(), bytes.unwrap_or_default()))
+    }
+
+    fn stop_audio(&mut self) {
+        if let Some(player) = &mut self.audio_player {
+            player.stop();
+        }
+    }
+
+    /// Splits the viewed texture into the frames of its .mcmeta animation once both are received.
+    fn prepare_texture_animation(&mut self) {
+        if let (Some(section), Some(texture)) = (&self.animation_section, self.viewed_image.as_ref().and_then(ImagePreview::pixels)) {
+            self.texture_animation = TextureAnimation::new(&texture, section);
+            self.animation_paused = false;
+            self.animation_offset = 0;
+            self.animation_start = Instant::now();
+            self.animation_elapsed = Duration::ZERO;
+        }
+    }
+
+    /// Moves a path of the workspace to another location relative to the workspace root, as a
+    /// single operation of the history.
+    fn relocate(&mut self, path: String, destination: String, sha: String, label: String) {
+        self.relocate_all(vec![(path, destination, sha)], label);
+    }
+
+    /// Moves several paths of the workspace as a single operation of the history.
+    fn relocate_all(&mut self, moves: Vec<(String, String, String)>, label: String) {
+        let Some(mut content) = self.workspace_content.clone() else { panic!() };
+        let paths = moves.iter().flat_map(|(path, destination, _)| [path.clone(), destination.clone()]).collect::<Vec<String>>();
+        self.history.begin(label, &content, &mut self.modification, &paths);
+        for (path, destination, sha) in moves {
+            let refactors = workspace::relocate_workspace_content(&mut content, path, destination, sha);
+            for (origin, (refactor, origin_sha)) in refactors {
+                self.modification.refactor(origin, refactor, origin_sha);
+            }
+        }
+        self.history.commit(&content, &mut self.modification);
+        self.workspace_content = Some(content);
+        self.index_workspace_content();
+    }
+
+    /// The selected paths that are not contained inside another selected path, leaving out the
+    /// submodules as they cannot be edited.
+    fn selection_roots(&self) -> Vec<String> {
+        self.selection.iter()
+            .filter(|path| !path.match_indices("/").any(|(index, _)| self.selection.contains(&path[..index])))
+            .filter(|path| !self.is_gitlink(path))
+            .cloned()
+            .collect()
+    }
+
+    /// Checks that a new path is valid and free, both in the workspace content and in the pending
+    /// changes which may hold files of directories that are not loaded yet.
+    fn check_new_path(&self, path: &str, origin: Option<&str>) -> Result<(), String> {
+        let Some(content) = &self.workspace_content else { return Ok(()) };
+        workspace::check_new_path(content, path, origin)?;
+        match self.modification.mode(&path.to_string()) {
+            Some(_) => Err(format!("{} already exists.", path)),
+            None => Ok(())
+        }
+    }
+
+    /// Checks a file imported into the workspace, which may replace an existing file as asked, but
+    /// neither a directory nor a submodule.
+    fn check_import_path(&self, path: &str) -> Result<(), String> {
+        let existing = self.workspace_content.as_ref().and_then(|content| workspace::find_content(content, path));
+        match existing {
+            Some(info) if info.mode == EntryMode::Submodule => Err(format!("{} is a submodule, a file cannot replace it.", path)),
+            Some(info) if matches!(info.content, PathContent::Directory(_)) => Err(format!("{} is a directory, a file cannot replace it.", path)),
+            Some(_) => Ok(()),
+            None => self.check_new_path(path, None)
+        }
+    }
+
+    /// Checks that the selected paths can be moved to their destinations, which must not be
+    /// inside themselves, be invalid, already exist, or be shared.
+    fn check_moves(&self, moves: &[(String, String, String)]) -> Result<(), String> {
+        let mut destinations = HashSet::new();
+        for (path, destination, _) in moves {
+            let name = path.split("/").last().unwrap();
+            if destination.starts_with(&format!("{}/", path)) {
+                return Err(format!("Cannot move {} inside itself.", name));
+            }
+            if !destinations.insert(destination.to_lowercase()) {
+                return Err(format!("Several selected items would become {}.", destination));
+            }
+            self.check_new_path(destination, Some(path)).map_err(|reason| format!("Cannot move {}: {}", name, reason))?;
+        }
+        Ok(())
+    }
+
+    /// The selected paths alongside the names a batch rename would give them.
+    fn renamed_selection(&self) -> Result<Vec<(String, String)>, String> {
+        let roots = self.selection_roots();
+        let names = roots.iter().map(|path| path.split("/").last().unwrap().to_string()).collect::<Vec<String>>();
+        let renamed = workspace::batch_rename(&names, self.rename_mode, &self.rename_pattern, &self.rename_replacement)?;
+        Ok(roots.into_iter().zip(renamed).collect())
+    }
+
+    /// Submodules are gitlinks to commits of other repositories and so cannot be edited here.
+    fn is_gitlink(&self, path: &str) -> bool {
+        self.workspace_content.as_ref()
+            .and_then(|content| workspace::find_content(content, path))
+            .is_some_and(|info| info.mode == EntryMode::Submodule)
+    }
+
     pub fn title(&self) -> String {
         String::from("Illusionna")
     }
@@ -212,6 +938,33 @@ impl IllusionnaApp {
         match message {
             Interaction::Tick => {
                 self.rotator = (self.rotator + 1) % 360;
+                if let Some(Some(progress)) = self.loading.get(&Loading::Sending) {
+                    self.displayed_progress += (progress - self.displayed_progress) * 0.1;
+                }
+                Task::none()
+            }
+            Interaction::AnimationFrame(now) => {
+                self.animation_elapsed = now.saturating_duration_since(self.animation_start);
+                if let Some(animation) = &mut self.texture_animation {
+                    if !self.animation_paused {
+                        animation.seek(self.animation_offset + (self.animation_elapsed.as_millis() / viewer::TICK.as_millis()) as u64);
+                    }
+                }
+                Task::none()
+            }
+            Interaction::ToggleAnimation => {
+                let Some(animation) = &self.texture_animation else { return Task::none() };
+                self.animation_paused = !self.animation_paused;
+                self.animation_offset = animation.tick();
+                self.animation_start = Instant::now();
+                self.animation_elapsed = Duration::ZERO;
+                Task::none()
+            }
+            Interaction::StepAnimation(forward) => {
+                let Some(animation) = &mut self.texture_animation else { return Task::none() };
+                animation.step(forward);
+                self.animation_paused = true;
+                self.animation_offset = animation.tick();
                 Task::none()
             }
             Interaction::StartDeviceFlow => {
@@ -222,6 +975,7 @@ impl IllusionnaApp {
             Interaction::CompleteDeviceFlow(crab) => {
                 self.crab = CrabState::Present(crab);
                 self.display = Display::ProjectSelection;
+                self.start_loading(Loading::Projects);
                 let usable_crab = self.get_crab().clone();
                 Task::perform(workspace::get_projects(usable_crab.clone()), |projects| {
                     return Interaction::ReceiveProjectInfos(projects)
@@ -230,6 +984,7 @@ impl IllusionnaApp {
             Interaction::ReceiveProjectInfos(projects) => {
                 let count = projects.len();
                 self.projects = Some(projects);
+                self.finish_loading(&Loading::Projects);
                 let crab = self.get_crab().clone();
                 Task::perform(wrapper::get_account_info(crab, count), |account| {
                     return Interaction::ReceiveAccountInfo(account)
@@ -303,6 +1058,7 @@ impl IllusionnaApp {
             }
             Interaction::OpenSelectedProject => {
                 self.display = Display::WorkspaceSelection;
+                self.start_loading(Loading::Workspaces);
                 let crab = self.get_crab().clone();
                 let project = self.selected_project.clone().unwrap();
                 Task::perform(workspace::get_workspaces(crab.clone(), project.clone(), false), |workspaces| {
@@ -318,6 +1074,7 @@ impl IllusionnaApp {
             }
             Interaction::ReceiveWorkspaceInfos(workspaces) => {
                 self.workspaces = Some(workspaces);
+                self.finish_loading(&Loading::Workspaces);
                 Task::none()
             }
             Interaction::DisplayProjectsList => {
@@ -328,6 +1085,7 @@ impl IllusionnaApp {
             Interaction::ToggleClosedWorkspaces(toggle) => {
                 self.workspaces = None;
                 self.show_closed = toggle;
+                self.start_loading(Loading::Workspaces);
                 let crab = self.get_crab().clone();
                 let project = self.selected_project.clone().unwrap();
                 Task::perform(workspace::get_workspaces(crab.clone(), project.clone(), toggle), |workspaces| {
@@ -377,11 +1135,37 @@ impl IllusionnaApp {
                 self.workspace_creation_description_text = "".to_string();
                 self.workspace_content = None;
                 self.workspace_content_filter = "".to_string();
+                self.workspace_filter_matches = None;
+                self.workspace_rows.clear();
+                self.workspace_scroll = (0.0, 0.0);
                 self.collapsed_directories.clear();
                 self.viewed_file_name = None;
                 self.viewed_file_path = None;
                 self.viewed_file_content = None;
+                self.viewed_text = None;
+                self.viewed_image = None;
+                self.viewed_audio = None;
+                self.stop_audio();
+                self.viewed_font = None;
+                self.viewed_font_face = None;
+                self.viewed_font_conflict = false;
+                self.upstream_dimensions = None;
+                self.animation_section = None;
+                self.texture_animation = None;
+                self.file_editor = None;
                 self.modification.reset();
+                self.history.clear();
+                self.show_pending_changes = false;
+                self.diff = None;
+                self.image_diff = None;
+                self.import_preview = None;
+                self.drop_target = "".to_string();
+                self.dragged = None;
+                self.selection.clear();
+                self.selection_anchor = None;
+                self.gitignores.clear();
+                self.notice = None;
+                self.staged_changes.clear();
                 self.display = Display::WorkspaceSelection;
                 Task::none()
             }
@@ -396,138 +1180,1016 @@ impl IllusionnaApp {
                 for x in self.workspaces.clone().unwrap() {
                     if x.workspace_full_id == workspace_full_id {
                         self.selected_workspace = Some(x.clone());
-                        return Task::perform(workspace::get_workspace_content(crab.clone(), x), |(x, y, z)| Interaction::ReceiveWorkspaceContent(x, y, z));
+                        self.start_loading(Loading::WorkspaceContent);
+                        let collaborators = wrapper::get_collaborators(crab.clone(), x.project.source_owner.clone(), x.project.source_name.clone());
+                        return Task::batch([
+                            Task::perform(workspace::get_workspace_content(crab.clone(), x), |(x, y, z)| Interaction::ReceiveWorkspaceContent(x, y, z)),
+                            Task::perform(collaborators, Interaction::ReceiveCollaborators)
+                        ]);
                     }
                 }
                 Task::none()
             }
-            Interaction::ReceiveWorkspaceContent(content, modification, private) => {
+            Interaction::ReceiveWorkspaceContent(mut content, modification, private) => {
+                let pending = std::mem::replace(&mut self.modification, modification);
+                self.modification.rebase(pending);
+                workspace::replay_workspace_content(&mut content, &self.modification);
                 self.workspace_content = Some(content);
-                self.modification = modification;
+                self.index_workspace_content();
+                self.finish_loading(&Loading::WorkspaceContent);
+                self.history.clear();
                 self.display = Display::WorkspaceContent;
-                Task::none()
+                self.gitignores.clear();
+                self.start_loading(Loading::Gitignores("".to_string()));
+                let crab = self.get_crab().clone();
+                let workspace = self.selected_workspace.clone().unwrap();
+                Task::perform(workspace::get_gitignores(crab, workspace, self.modification.gitignores()), |gitignores| {
+                    Interaction::ReceiveGitignores("".to_string(), gitignores)
+                })
             }
             Interaction::FilterWorkspaceContent(input) => {
                 self.workspace_content_filter = input;
+                self.index_workspace_content();
+                self.workspace_scroll.0 = 0.0;
+                scrollable::scroll_to(scrollable::Id::new("workspace-tree"), scrollable::AbsoluteOffset { x: 0.0, y: 0.0 })
+            }
+            Interaction::ScrollWorkspaceContent(viewport) => {
+                self.workspace_scroll = (viewport.absolute_offset().y, viewport.bounds().height);
                 Task::none()
             }
             Interaction::CollapseDirectory(path) => {
-                let mut vec = self.collapsed_directories.clone();
-                vec.push(path);
-                self.collapsed_directories = vec;
+                self.collapsed_directories.insert(path);
+                self.flatten_workspace_content();
                 Task::none()
             }
             Interaction::ExpandDirectory(path) => {
-                let mut vec = self.collapsed_directories.clone();
-                vec.retain(|x| x != &path);
-                self.collapsed_directories = vec;
+                self.collapsed_directories.remove(&path);
+                self.flatten_workspace_content();
+                let unloaded = workspace::find_content(self.workspace_content.as_ref().unwrap(), &path)
+                    .is_some_and(|info| matches!(&info.content, PathContent::Directory(directory) if !directory.loaded));
+                if unloaded && self.modification.can_load(&path) && !self.is_loading(&Loading::Directory(path.clone())) {
+                    self.start_loading(Loading::Directory(path.clone()));
+                    let crab = self.get_crab().clone();
+                    let workspace = self.selected_workspace.clone().unwrap();
+                    let sha = self.modification.upstream_info(&path).unwrap().sha.clone();
+                    return Task::perform(workspace::get_directory_content(crab, workspace, sha), move |parts| {
+                        Interaction::ReceiveDirectoryContent(path.clone(), parts)
+                    });
+                }
+                Task::none()
+            }
+            Interaction::ReceiveDirectoryContent(path, parts) => {
+                self.finish_loading(&Loading::Directory(path.clone()));
+                if let Some(content) = self.workspace_content.as_mut() {
+                    workspace::load_workspace_directory(content, &mut self.modification, path.clone(), parts);
+                }
+                self.index_workspace_content();
+                let gitignores = self.modification.gitignores().into_iter()
+                    .filter(|(path, _)| !self.gitignores.contains_key(path))
+                    .collect::<Vec<(String, String)>>();
+                if gitignores.is_empty() {
+                    return Task::none();
+                }
+                self.start_loading(Loading::Gitignores(path.clone()));
+                let crab = self.get_crab().clone();
+                let workspace = self.selected_workspace.clone().unwrap();
+                Task::perform(workspace::get_gitignores(crab, workspace, gitignores), move |gitignores| {
+                    Interaction::ReceiveGitignores(path.clone(), gitignores)
+                })
+            }
+            Interaction::ReceiveGitignores(path, gitignores) => {
+                self.finish_loading(&Loading::Gitignores(path));
+                self.gitignores.extend(gitignores);
+                Task::none()
+            }
+            Interaction::DismissNotice => {
+                self.notice = None;
                 Task::none()
             }
-            Interaction::ViewFile(sha, path) => {
+            Interaction::ViewFile(mut sha, path) => {
+                if self.is_gitlink(&path) {
+                    return Task::none();
+                }
                 self.viewed_file_path = Some(path.clone());
                 self.viewed_file_name = Some(path.split("/").last().unwrap().to_string());
                 self.viewed_file_content = None;
+                self.viewed_text = None;
+                self.viewed_image = None;
+                self.viewed_audio = None;
+                self.stop_audio();
+                self.viewed_font = None;
+                self.viewed_font_face = None;
+                self.viewed_font_conflict = false;
+                self.upstream_dimensions = None;
+                self.animation_section = None;
+                self.texture_animation = None;
+                self.file_editor = None;
+                self.diff = None;
+                self.image_diff = None;
+                let mcmeta = self.fetch_mcmeta(&path);
                 let content = self.modification.view(&path);
                 if content.is_some() {
                     match content.unwrap() {
                         FileContent::Bytes(bytes) => {
-                            return Task::done(Interaction::ProcessViewingContent(bytes.clone()));
+                            return Task::batch([Task::done(Interaction::ProcessViewingContent(Ok(bytes.clone()))), mcmeta]);
                         }
-                        _ => {}
+                        FileContent::Sha(pending_sha) => sha = pending_sha.clone()
                     }
                 }
+                self.start_loading(Loading::File);
                 let crab = self.get_crab().clone();
                 let workspace = self.selected_workspace.clone().unwrap();
-                Task::perform(workspace::get_file_content(crab, workspace, sha), Interaction::ProcessViewingContent)
+                Task::batch([Task::perform(workspace::get_file_content(crab, workspace, sha), Interaction::ProcessViewingContent), mcmeta])
+            }
+            Interaction::ReceiveMcmeta(path, bytes) => {
+                if self.viewed_file_path.as_ref() == Some(&path) {
+                    self.animation_section = viewer::parse_mcmeta(&bytes);
+                    self.prepare_texture_animation();
+                }
+                Task::none()
             }
             Interaction::ProcessViewingContent(bytes) => {
-                self.viewed_file_content = Some(bytes);
+                self.finish_loading(&Loading::File);
+                let bytes = match bytes {
+                    Ok(bytes) => bytes,
+                    Err(error) => {
+                        self.notice = Some(format!("The file could not be loaded: {}", error));
+                        return Task::none();
+                    }
+                };
+                let name = self.viewed_file_name.clone().unwrap_or_default();
+                if audio::sniff_audio(&bytes).is_some() {
+                    let path = self.viewed_file_path.clone().unwrap_or_default();
+                    self.viewed_file_content = Some(bytes.clone());
+                    self.start_loading(Loading::File);
+                    return Task::perform(audio::decode_audio(bytes), move |clip| Interaction::ReceiveAudio(path.clone(), clip));
+                }
+                if let Some(metadata) = viewer::font_metadata(&bytes) {
+                    let path = self.viewed_file_path.clone().unwrap_or_default();
+                    let hash = content_hash(&bytes);
+                    let face = self.font_face(&metadata);
+                    self.viewed_font = Some(metadata);
+                    self.viewed_file_content = Some(bytes.clone());
+                    return match self.loaded_fonts.get(&face) {
+                        None => font::load(bytes).map(move |result| Interaction::ReceiveFont(path.clone(), hash, result.is_ok())),
+                        Some(_) => Task::done(Interaction::ReceiveFont(path, hash, true))
+                    };
+                }
+                self.viewed_text = viewer::text_preview(&name, &bytes);
+                self.viewed_file_content = Some(bytes.clone());
+                if viewer::sniff_image(&name, &bytes).is_some() {
+                    let path = self.viewed_file_path.clone().unwrap_or_default();
+                    self.start_loading(Loading::File);
+                    return Task::perform(viewer::decode_image(name, bytes), move |preview| Interaction::ReceiveImage(path.clone(), preview));
+                }
                 Task::none()
             }
-            Interaction::SelectFiles(is_inside_dir, import_location_path) => {
-                Task::perform(workspace::import_files(is_inside_dir, import_location_path), Interaction::SetFiles)
+            Interaction::ReceiveImage(path, preview) => {
+                if self.viewed_file_path.as_ref() != Some(&path) {
+                    return Task::none();
+                }
+                self.finish_loading(&Loading::File);
+                self.viewed_image = preview;
+                self.animation_start = Instant::now();
+                self.animation_elapsed = Duration::ZERO;
+                self.prepare_texture_animation();
+                let origin = self.modification.origin(&path).cloned().unwrap_or(path.clone());
+                let replacement = matches!(self.modification.status(&path), Some(ChangeKind::Replaced | ChangeKind::Renamed));
+                match self.modification.upstream_info(&origin) {
+                    Some(info) if replacement && matches!(self.viewed_image, Some(ImagePreview::Raster { .. })) => {
+                        let crab = self.get_crab().clone();
+                        let workspace = self.selected_workspace.clone().unwrap();
+                        Task::perform(workspace::get_file_content(crab, workspace, info.sha.clone()), move |bytes| {
+                            Interaction::ReceiveUpstreamImage(path.clone(), bytes.unwrap_or_default())
+                        })
+                    }
+                    _ => Task::none()
+                }
             }
-            Interaction::SetFiles(files) => {
-                let Some(mut content) = self.workspace_content.clone() else { panic!() };
-                workspace::append_workspace_content(&mut content, files.keys().map(|x| x.to_string()).collect());
-                self.workspace_content = Some(content);
-                for file in files {
-                    self.modification.set(file.0, FileContent::Bytes(file.1));
+            Interaction::ReceiveAudio(path, clip) => {
+                if self.viewed_file_path.as_ref() != Some(&path) {
+                    return Task::none();
+                }
+                self.finish_loading(&Loading::File);
+                match clip {
+                    Some(Ok(clip)) => self.viewed_audio = Some(clip),
+                    Some(Err(error)) => self.notice = Some(format!("{} could not be decoded: {}", path, error)),
+                    None => {}
                 }
                 Task::none()
             }
-            Interaction::RefactorFiles(path) => {
-                self.refactors.insert(path.clone(), path.split("/").last().unwrap().to_string());
+            Interaction::ToggleAudio => {
+                let Some(clip) = &self.viewed_audio else { return Task::none() };
+                if self.audio_player.is_none() {
+                    match AudioPlayer::new() {
+                        Ok(player) => self.audio_player = Some(player),
+                        Err(error) => {
+                            self.notice = Some(format!("No audio output device is available: {}", error));
+                            return Task::none();
+                        }
+                    }
+                }
+                let player = self.audio_player.as_ref().unwrap();
+                if player.is_playing() { player.pause() } else { player.play(clip) }
                 Task::none()
             }
-            Interaction::RefactorInput(path, input) => {
-                if !input.starts_with("/") && !input.contains("//") {
-                    self.refactors.insert(path, input);
+            Interaction::SeekAudio(seconds) => {
+                if let (Some(clip), Some(player)) = (&self.viewed_audio, &self.audio_player) {
+                    player.seek(clip, Duration::from_secs_f32(seconds));
                 }
                 Task::none()
             }
-            Interaction::PushRefactor(path, sha) => {
-                let check = self.refactors.get(&path).unwrap();
-                if !check.ends_with("/") && !check.ends_with(".") {
-                    let Some(mut content) = self.workspace_content.clone() else { panic!() };
-                    let refactors = workspace::refactor_workspace_content(&mut content, path.clone(), self.refactors.remove(&path).unwrap(), sha);
-                    self.workspace_content = Some(content);
-                    for (origin, (refactor, origin_sha)) in refactors {
-                        self.modification.refactor(origin, refactor, origin_sha);
-                    }
+            Interaction::ReceiveFont(path, hash, loaded) => {
+                let Some(metadata) = self.viewed_font.clone().filter(|_| self.viewed_file_path.as_ref() == Some(&path)) else {
+                    return Task::none();
+                };
+                if !loaded {
+                    // Without the face there is no specimen to show, so the file is shown as binary.
+                    self.notice = Some(format!("{} could not be loaded as a font.", path));
+                    self.viewed_font = None;
+                    return Task::none();
+                }
+                // The renderer picks the first face loaded with the same name, weight and style, so a
+                // face can only be previewed when it is the one that was loaded first.
+                let face = self.font_face(&metadata);
+                match *self.loaded_fonts.entry(face).or_insert(Some(hash)) {
+                    Some(loaded) if loaded == hash => self.viewed_font_face = Some(face),
+                    _ => self.viewed_font_conflict = true
                 }
                 Task::none()
             }
-            Interaction::EraseFiles(path) => {
-                let Some(mut content) = self.workspace_content.clone() else { panic!() };
-                workspace::remove_workspace_content(&mut content, path.clone());
-                self.workspace_content = Some(content);
-                self.modification.erase(path);
+            Interaction::UpdateFontSample(sample) => {
+                self.font_sample = sample;
                 Task::none()
             }
-            Interaction::ModificationNameInput(input) => {
-                self.modification_name = input;
+            Interaction::ReceiveUpstreamImage(path, bytes) => {
+                if self.viewed_file_path.as_ref() == Some(&path) {
+                    self.upstream_dimensions = viewer::image_dimensions(&path, &bytes);
+                }
                 Task::none()
             }
-            Interaction::SendChanges => {
-                if !self.modification_name.is_empty() {
-                    let crab = self.get_crab().clone();
-                    let workspace = self.selected_workspace.clone().unwrap();
-                    let modification = self.modification.clone(); // I do not like that at all.
-                    self.modification.reset();
-                    let modification_name = self.modification_name.clone();
-                    Task::perform(workspace::send_contents(crab, workspace, modification, modification_name), |_| Interaction::ResetChanges)
+            Interaction::SelectFiles(is_inside_dir, import_location_path) => {
+                if self.is_gitlink(&import_location_path) || is_inside_dir && self.ignore_rules_pending() {
+                    return Task::none();
                 }
-                else {
-                    Task::none()
+                if is_inside_dir {
+                    Task::perform(workspace::import_files(true, import_location_path), Interaction::SetFiles)
+                } else {
+                    Task::perform(workspace::import_files(false, import_location_path), Interaction::ReplaceFiles)
                 }
             }
-            Interaction::ResetChanges => {
-                self.refactors.clear();
-                self.modification.reset();
-                self.modification_name = "".to_string();
-                let crab = self.get_crab();
-                let workspace = self.selected_workspace.clone().unwrap();
-                Task::perform(workspace::get_workspace_content(crab.clone(), workspace), |(x, y, z)| Interaction::ReceiveWorkspaceContent(x, y, z))
+            Interaction::SelectFolder(import_location_path) => {
+                if self.is_gitlink(&import_location_path) || self.ignore_rules_pending() {
+                    return Task::none();
+                }
+                Task::perform(workspace::import_folder(import_location_path), Interaction::PreviewImport)
             }
-        }
-    }
-
-    pub fn view(&self) -> Element<'_, Interaction, Theme, Renderer> {
-        match &self.display {
-            Display::GithubConnexion => self.github_connection(),
-            Display::ProjectSelection => self.project_selection(),
-            Display::WorkspaceCreation => self.workspace_creation(),
-            Display::WorkspaceSelection => self.workspace_selection(),
-            Display::WorkspaceContent => self.workspace_content()
-        }
-    }
-
-    fn github_connection(&self) -> Element<'_, Interaction, Theme, Renderer> {
-        let illusionna_title = Image::new(image::Handle::from_bytes(include_bytes!("../resources/title.png").as_slice()))
-            .filter_method(FilterMethod::Nearest)
-            .width(Length::Fixed(426f32))
-            .height(Length::Fixed(240f32));
+            Interaction::PreviewImport(files) => {
+                if self.ignore_rules_pending() {
+                    return Task::none();
+                }
+                let files = self.skip_ignored(files);
+                if !files.is_empty() {
+                    self.import_preview = Some(files);
+                }
+                Task::none()
+            }
+            Interaction::ApplyImport => {
+                match self.import_preview.take() {
+                    Some(files) => Task::done(Interaction::SetFiles(files.into_iter().collect())),
+                    None => Task::none()
+                }
+            }
+            Interaction::CancelImport => {
+                self.import_preview = None;
+                Task::none()
+            }
+            Interaction::HoverDropTarget(path) => {
+                self.drop_target = path;
+                Task::none()
+            }
+            Interaction::FilesHovered => {
+                self.files_hovered = true;
+                Task::none()
+            }
+            Interaction::FilesHoveredLeft => {
+                self.files_hovered = false;
+                self.drop_target = "".to_string();
+                Task::none()
+            }
+            Interaction::FileDropped(path) => {
+                self.files_hovered = false;
+                if !matches!(self.display, Display::WorkspaceContent) || !self.tree_hovered || self.is_gitlink(&self.drop_target) || self.ignore_rules_pending() {
+                    return Task::none();
+                }
+                self.dropped_files.get_or_insert_with(|| (self.drop_target.clone(), vec![])).1.push(path);
+                Task::none()
+            }
+            Interaction::ImportDroppedFiles => {
+                let Some((target, paths)) = self.dropped_files.take() else { return Task::none() };
+                Task::perform(workspace::import_dropped(paths, target), |files| {
+                    Interaction::SetFiles(files.into_iter().collect())
+                })
+            }
+            Interaction::SetFiles(files) => {
+                if self.ignore_rules_pending() {
+                    return Task::none();
+                }
+                let files = self.skip_ignored(files);
+                let mut accepted = HashSet::new();
+                let mut rejected = vec![];
+                let files = files.into_iter()
+                    .filter(|(path, _)| {
+                        let check = self.check_import_path(path).and_then(|_| match accepted.insert(path.to_lowercase()) {
+                            true => Ok(()),
+                            false => Err(format!("{} only differs from another imported file by case.", path))
+                        });
+                        check.map_err(|reason| rejected.push(reason)).is_ok()
+                    })
+                    .collect::<HashMap<String, Vec<u8>>>();
+                if !rejected.is_empty() {
+                    rejected.sort();
+                    let mut listed = rejected.iter().take(5).cloned().collect::<Vec<String>>().join(" ");
+                    if rejected.len() > 5 {
+                        listed = format!("{} And {} more.", listed, rejected.len() - 5);
+                    }
+                    let reasons = format!("Skipped {} file(s) that cannot be imported: {}", rejected.len(), listed);
+                    self.notice = Some(match self.notice.take() {
+                        Some(notice) => format!("{} {}", notice, reasons),
+                        None => reasons
+                    });
+                }
+                Task::done(Interaction::ReplaceFiles(files))
+            }
+            Interaction::ReplaceFiles(files) => {
+                if files.is_empty() {
+                    return Task::none();
+                }
+                let Some(mut content) = self.workspace_content.clone() else { panic!() };
+                let paths = files.keys().map(|x| x.to_string()).collect::<Vec<String>>();
+                self.history.begin(format!("Import {} File(s)", paths.len()), &content, &mut self.modification, &paths);
+                workspace::append_workspace_content(&mut content, paths);
+                let mut symlinks = files.keys().filter(|path| self.modification.is_symlink(path)).cloned().collect::<Vec<String>>();
+                symlinks.sort();
+                for file in files {
+                    self.modification.set(file.0, FileContent::Bytes(file.1));
+                }
+                self.history.commit(&content, &mut self.modification);
+                if !symlinks.is_empty() {
+                    let replaced = format!("Replaced {} symbolic link(s) by regular files: {}", symlinks.len(), symlinks.join(", "));
+                    self.notice = Some(match self.notice.take() {
+                        Some(notice) => format!("{} {}", notice, replaced),
+                        None => replaced
+                    });
+                }
+                self.workspace_content = Some(content);
+                self.index_workspace_content();
+                Task::none()
+            }
+            Interaction::RefactorFiles(path) => {
+                if self.is_gitlink(&path) {
+                    return Task::none();
+                }
+                self.refactors.insert(path.clone(), path.split("/").last().unwrap().to_string());
+                Task::none()
+            }
+            Interaction::RefactorInput(path, input) => {
+                if !input.starts_with("/") && !input.contains("//") {
+                    self.refactors.insert(path, input);
+                }
+                Task::none()
+            }
+            Interaction::PushRefactor(path, sha) => {
+                let Some(input) = self.refactors.get(&path) else { return Task::none() };
+                let destination = match workspace::resolve_refactor(&path, input) {
+                    Ok(destination) => destination,
+                    Err(reason) => {
+                        self.notice = Some(reason);
+                        return Task::none();
+                    }
+                };
+                let check = if input.ends_with("/") {
+                    Err(format!("\"{}\" is not a valid name.", input))
+                } else if destination.starts_with(&format!("{}/", path)) {
+                    Err(format!("{} cannot be moved inside itself.", path))
+                } else if destination == path {
+                    Ok(())
+                } else {
+                    self.check_new_path(&destination, Some(&path))
+                };
+                match check {
+                    Ok(()) => {
+                        self.refactors.remove(&path);
+                        if destination != path {
+                            let label = format!("Rename {}", path.split("/").last().unwrap());
+                            self.relocate(path, destination, sha, label);
+                        }
+                    }
+                    Err(reason) => self.notice = Some(reason)
+                }
+                Task::none()
+            }
+            Interaction::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
+            Interaction::ClickRow(path, sha) => {
+                if self.modifiers.shift() && self.selection_anchor.is_some() {
+                    let anchor = self.selection_anchor.clone().unwrap();
+                    let position = |target: &String| self.workspace_rows.iter().position(|row| &row.path == target);
+                    if let (Some(start), Some(end)) = (position(&anchor), position(&path)) {
+                        let range = self.workspace_rows[start.min(end)..=start.max(end)].iter().map(|row| row.path.clone()).collect::<Vec<String>>();
+                        self.selection.extend(range);
+                    }
+                    return Task::none();
+                }
+                self.selection_anchor = Some(path.clone());
+                if self.modifiers.command() {
+                    if !self.selection.remove(&path) {
+                        self.selection.insert(path);
+                    }
+                    return Task::none();
+                }
+                self.selection.clear();
+                match sha {
+                    Some(sha) => Task::done(Interaction::ViewFile(sha, path)),
+                    None => Task::none()
+                }
+            }
+            Interaction::SelectMatches => {
+                let filter = self.workspace_content_filter.to_lowercase();
+                if let (Some(content), Some(matches)) = (&self.workspace_content, &self.workspace_filter_matches) {
+                    let files = matches.iter()
+                        .filter(|path| path.to_lowercase().contains(&filter))
+                        .filter(|path| workspace::find_content(content, path).is_some_and(|info| matches!(info.content, PathContent::File(_))))
+                        .cloned()
+                        .collect::<Vec<String>>();
+                    self.selection.extend(files);
+                }
+                Task::none()
+            }
+            Interaction::ClearSelection => {
+                self.selection.clear();
+                self.selection_anchor = None;
+                Task::none()
+            }
+            Interaction::DeleteSelection => {
+                let roots = self.selection_roots();
+                if roots.is_empty() {
+                    return Task::none();
+                }
+                let Some(mut content) = self.workspace_content.clone() else { panic!() };
+                self.history.begin(format!("Remove {} Item(s)", roots.len()), &content, &mut self.modification, &roots);
+                for path in roots {
+                    workspace::remove_workspace_content(&mut content, path.clone());
+                    self.modification.erase(path);
+                }
+                self.history.commit(&content, &mut self.modification);
+                self.workspace_content = Some(content);
+                self.selection.clear();
+                self.index_workspace_content();
+                Task::none()
+            }
+            Interaction::BulkDestinationInput(input) => {
+                if !input.starts_with("/") && !input.contains("//") {
+                    self.bulk_destination = input;
+                }
+                Task::none()
+            }
+            Interaction::MoveSelection => {
+                let folder = self.bulk_destination.trim().trim_end_matches("/").to_string();
+                if folder.split("/").any(|segment| segment == "." || segment == "..") || self.is_gitlink(&folder) {
+                    self.notice = Some(format!("\"{}\" is not a valid folder.", folder));
+                    return Task::none();
+                }
+                let moves = self.selection_roots().into_iter()
+                    .filter_map(|path| {
+                        let name = path.split("/").last().unwrap().to_string();
+                        let destination = if folder.is_empty() { name } else { format!("{}/{}", folder, name) };
+                        let sha = workspace::find_content(self.workspace_content.as_ref()?, &path)?.sha.clone();
+                        (destination != path).then_some((path, destination, sha))
+                    })
+                    .collect::<Vec<(String, String, String)>>();
+                if moves.is_empty() {
+                    return Task::none();
+                }
+                if let Err(reason) = self.check_moves(&moves) {
+                    self.notice = Some(reason);
+                    return Task::none();
+                }
+                let label = format!("Move {} Item(s)", moves.len());
+                self.relocate_all(moves, label);
+                self.selection.clear();
+                Task::none()
+            }
+            Interaction::RenameModeSelected(mode) => {
+                self.rename_mode = mode;
+                Task::none()
+            }
+            Interaction::RenamePatternInput(input) => {
+                self.rename_pattern = input;
+                Task::none()
+            }
+            Interaction::RenameReplacementInput(input) => {
+                self.rename_replacement = input;
+                Task::none()
+            }
+            Interaction::RenameSelection => {
+                let renamed = match self.renamed_selection() {
+                    Ok(renamed) => renamed,
+                    Err(reason) => {
+                        self.notice = Some(reason);
+                        return Task::none();
+                    }
+                };
+                let moves = renamed.into_iter()
+                    .filter_map(|(path, name)| {
+                        let destination = match path.rsplit_once("/") {
+                            Some((parent, _)) => format!("{}/{}", parent, name),
+                            None => name
+                        };
+                        let sha = workspace::find_content(self.workspace_content.as_ref()?, &path)?.sha.clone();
+                        (destination != path).then_some((path, destination, sha))
+                    })
+                    .collect::<Vec<(String, String, String)>>();
+                if moves.is_empty() {
+                    return Task::none();
+                }
+                if let Err(reason) = self.check_moves(&moves) {
+                    self.notice = Some(reason);
+                    return Task::none();
+                }
+                let label = format!("Rename {} Item(s)", moves.len());
+                self.relocate_all(moves, label);
+                self.selection.clear();
+                Task::none()
+            }
+            Interaction::HoverTree(hovered) => {
+                self.tree_hovered = hovered;
+                if !hovered {
+                    self.drop_target = "".to_string();
+                }
+                Task::none()
+            }
+            Interaction::StartDrag(path) => {
+                if !self.is_gitlink(&path) {
+                    self.dragged = Some(path);
+                }
+                Task::none()
+            }
+            Interaction::FinishDrag => {
+                let Some(path) = self.dragged.take() else { return Task::none() };
+                let target = self.drop_target.clone();
+                let parent = path.rsplit_once("/").map(|(parent, _)| parent.to_string()).unwrap_or_default();
+                if !self.tree_hovered || target == path || target == parent {
+                    return Task::none();
+                }
+                let name = path.split("/").last().unwrap().to_string();
+                let destination = if target.is_empty() { name.clone() } else { format!("{}/{}", target, name) };
+                if target.starts_with(&format!("{}/", path)) {
+                    self.notice = Some(format!("Cannot move {} inside itself.", name));
+                }
+                else if self.is_gitlink(&target) {
+                    self.notice = Some(format!("Cannot move {} inside a submodule.", name));
+                }
+                else if let Err(reason) = self.check_new_path(&destination, Some(&path)) {
+                    self.notice = Some(format!("Cannot move {}: {}", name, reason));
+                }
+                else if let Some(sha) = self.workspace_content.as_ref().and_then(|content| workspace::find_content(content, &path)).map(|info| info.sha.clone()) {
+                    self.relocate(path, destination, sha, format!("Move {}", name));
+                }
+                Task::none()
+            }
+            Interaction::DuplicateFiles(path) => {
+                if self.is_gitlink(&path) {
+                    return Task::none();
+                }
+                let name = path.split("/").last().unwrap();
+                let is_dir = self.workspace_content.as_ref()
+                    .and_then(|content| workspace::find_content(content, &path))
+                    .is_some_and(|info| matches!(info.content, PathContent::Directory(_)));
+                let copy = match name.rsplit_once(".") {
+                    Some((stem, extension)) if !is_dir && !stem.is_empty() => format!("{}_copy.{}", stem, extension),
+                    _ => format!("{}_copy", name)
+                };
+                self.duplicates.insert(path, copy);
+                Task::none()
+            }
+            Interaction::DuplicateInput(path, input) => {
+                if !input.starts_with("/") && !input.contains("//") {
+                    self.duplicates.insert(path, input);
+                }
+                Task::none()
+            }
+            Interaction::PushDuplicate(path) => {
+                let Some(input) = self.duplicates.remove(&path) else { return Task::none() };
+                if input.is_empty() {
+                    return Task::none();
+                }
+                let destination = match workspace::resolve_refactor(&path, &input) {
+                    Ok(destination) => destination,
+                    Err(reason) => {
+                        self.notice = Some(reason);
+                        return Task::none();
+                    }
+                };
+                if input.ends_with("/") {
+                    self.notice = Some(format!("\"{}\" is not a valid location.", input));
+                    return Task::none();
+                }
+                if destination == path || destination.starts_with(&format!("{}/", path)) {
+                    self.notice = Some(format!("{} cannot be duplicated inside itself.", path));
+                    return Task::none();
+                }
+                if let Err(reason) = self.check_new_path(&destination, None) {
+                    self.notice = Some(reason);
+                    return Task::none();
+                }
+                let Some(mut content) = self.workspace_content.clone() else { panic!() };
+                let label = format!("Duplicate {}", path.split("/").last().unwrap());
+                self.history.begin(label, &content, &mut self.modification, &[destination.clone()]);
+                let copies = workspace::duplicate_workspace_content(&mut content, path, destination);
+                for (origin, (copy, origin_sha)) in copies {
+                    self.modification.copy(&origin, copy, origin_sha);
+                }
+                self.history.commit(&content, &mut self.modification);
+                self.workspace_content = Some(content);
+                self.index_workspace_content();
+                Task::none()
+            }
+            Interaction::EraseFiles(path) => {
+                if self.is_gitlink(&path) {
+                    return Task::none();
+                }
+                let Some(mut content) = self.workspace_content.clone() else { panic!() };
+                let label = format!("Remove {}", path.split("/").last().unwrap());
+                self.history.begin(label, &content, &mut self.modification, &[path.clone()]);
+                workspace::remove_workspace_content(&mut content, path.clone());
+                self.modification.erase(path);
+                self.history.commit(&content, &mut self.modification);
+                self.workspace_content = Some(content);
+                self.index_workspace_content();
+                Task::none()
+            }
+            Interaction::CreateEntry(directory, folder) => {
+                if self.is_gitlink(&directory) {
+                    return Task::none();
+                }
+                self.creation = Some(Creation { directory, folder, input: "".to_string() });
+                Task::none()
+            }
+            Interaction::CreationInput(input) => {
+                if let Some(creation) = &mut self.creation {
+                    if !input.starts_with("/") && !input.contains("//") {
+                        creation.input = input;
+                    }
+                }
+                Task::none()
+            }
+            Interaction::PushCreation => {
+                let Some(creation) = self.creation.take() else { return Task::none() };
+                let name = creation.input.trim().to_string();
+                if name.is_empty() {
+                    return Task::none();
+                }
+                if name.ends_with("/") {
+                    self.notice = Some(format!("\"{}\" is not a valid name.", name));
+                    return Task::none();
+                }
+                let path = if creation.directory.is_empty() { name.clone() } else { format!("{}/{}", creation.directory, name) };
+                if let Err(reason) = self.check_new_path(&path, None) {
+                    self.notice = Some(reason);
+                    return Task::none();
+                }
+                let file = if creation.folder { format!("{}/{}", path, workspace::FOLDER_PLACEHOLDER) } else { path.clone() };
+                let Some(mut content) = self.workspace_content.clone() else { panic!() };
+                let label = format!("Create {}", name);
+                self.history.begin(label, &content, &mut self.modification, &[file.clone()]);
+                workspace::append_workspace_content(&mut content, vec![file.clone()]);
+                self.modification.set(file.clone(), FileContent::Bytes(vec![]));
+                self.history.commit(&content, &mut self.modification);
+                self.workspace_content = Some(content);
+                self.collapsed_directories.remove(&creation.directory);
+                self.index_workspace_content();
+                if creation.folder {
+                    Task::none()
+                } else {
+                    Task::done(Interaction::ViewFile("".to_string(), file))
+                }
+            }
+            Interaction::TogglePendingChanges => {
+                self.show_pending_changes = !self.show_pending_changes;
+                Task::none()
+            }
+            Interaction::RevertChange(path) => {
+                let Some(mut content) = self.workspace_content.clone() else { panic!() };
+                let mut paths = vec![path.clone()];
+                paths.extend(self.modification.origin(&path).cloned());
+                let label = format!("Revert {}", path.split("/").last().unwrap());
+                self.history.begin(label, &content, &mut self.modification, &paths);
+                workspace::revert_workspace_content(&mut content, &mut self.modification, path);
+                self.history.commit(&content, &mut self.modification);
+                self.workspace_content = Some(content);
+                self.index_workspace_content();
+                // The toggle of the panel disappears along with the last pending change.
+                self.show_pending_changes &= self.modification.present();
+                Task::none()
+            }
+            Interaction::Undo => {
+                if let Some(content) = &mut self.workspace_content {
+                    if self.history.undo(content, &mut self.modification).is_some() {
+                        self.refactors.clear();
+                    }
+                }
+                self.index_workspace_content();
+                self.show_pending_changes &= self.modification.present();
+                self.reload_viewed_file()
+            }
+            Interaction::Redo => {
+                if let Some(content) = &mut self.workspace_content {
+                    if self.history.redo(content, &mut self.modification).is_some() {
+                        self.refactors.clear();
+                    }
+                }
+                self.index_workspace_content();
+                self.show_pending_changes &= self.modification.present();
+                self.reload_viewed_file()
+            }
+            Interaction::ModificationNameInput(input) => {
+                self.modification_name = input;
+                Task::none()
+            }
+            Interaction::StageChange(path, staged) => {
+                if staged {
+                    self.staged_changes.insert(path);
+                }
+                else {
+                    self.staged_changes.remove(&path);
+                }
+                Task::none()
+            }
+            Interaction::StageAllChanges(staged) => {
+                self.staged_changes.clear();
+                if staged {
+                    self.staged_changes.extend(self.modification.pending().into_iter().map(|change| change.path));
+                }
+                Task::none()
+            }
+            Interaction::CommitDescriptionAction(action) => {
+                self.commit_description.perform(action);
+                Task::none()
+            }
+            Interaction::ReceiveCollaborators(collaborators) => {
+                let account = self.account.as_ref().map(|account| account.name.clone());
+                self.collaborators = collaborators.into_iter().filter(|collaborator| Some(&collaborator.login) != account.as_ref()).collect();
+                Task::none()
+            }
+            Interaction::AddCoAuthor(collaborator) => {
+                if !self.co_authors.contains(&collaborator) {
+                    self.co_authors.push(collaborator);
+                }
+                Task::none()
+            }
+            Interaction::RemoveCoAuthor(login) => {
+                self.co_authors.retain(|collaborator| collaborator.login != login);
+                Task::none()
+            }
+            Interaction::OpenSettings => {
+                self.ignore_patterns = text_editor::Content::with_text(&self.settings.ignore_patterns);
+                self.settings_origin = std::mem::replace(&mut self.display, Display::Settings);
+                Task::none()
+            }
+            Interaction::CloseSettings => {
+                self.settings = wrapper::get_stored_settings().unwrap_or_default();
+                self.display = self.settings_origin.clone();
+                Task::none()
+            }
+            Interaction::AuthorNameInput(input) => {
+                self.settings.author_name = input;
+                Task::none()
+            }
+            Interaction::AuthorEmailInput(input) => {
+                self.settings.author_email = input;
+                Task::none()
+            }
+            Interaction::ToggleVersionTrailer(toggle) => {
+                self.settings.record_version = toggle;
+                Task::none()
+            }
+            Interaction::CustomTrailerInput(input) => {
+                self.settings.custom_trailer = input;
+                Task::none()
+            }
+            Interaction::IgnorePatternsAction(action) => {
+                self.ignore_patterns.perform(action);
+                self.settings.ignore_patterns = self.ignore_patterns.text().trim_end().to_string();
+                Task::none()
+            }
+            Interaction::ViewDiff(path) => {
+                let origin = self.modification.origin(&path).cloned().unwrap_or(path.clone());
+                let upstream_sha = self.modification.upstream_info(&origin)
+                    .filter(|info| !matches!(info.mode, EntryMode::Directory | EntryMode::Submodule))
+                    .map(|info| info.sha.clone());
+                let pending = self.modification.view(&path).cloned();
+                if upstream_sha.is_none() && pending.is_none() {
+                    return Task::none();
+                }
+                self.start_loading(Loading::Diff);
+                let crab = self.get_crab().clone();
+                let workspace = self.selected_workspace.clone().unwrap();
+                let diff_path = path.clone();
+                Task::perform(async move {
+                    let (upstream, pending) = workspace::get_diff_contents(crab, workspace, upstream_sha, pending).await?;
+                    Ok(diff::diff_change(path, upstream, pending).await)
+                }, move |change_diff| Interaction::ReceiveDiff(diff_path.clone(), change_diff))
+            }
+            Interaction::ReceiveDiff(path, change_diff) => {
+                self.finish_loading(&Loading::Diff);
+                self.diff = None;
+                self.image_diff = None;
+                let change_diff = match change_diff {
+                    Ok(change_diff) => change_diff,
+                    Err(error) => {
+                        self.notice = Some(format!("The differences of {} could not be loaded: {}", path, error));
+                        return Task::none();
+                    }
+                };
+                match change_diff {
+                    ChangeDiff::Image(mut image_diff) => {
+                        image_diff.compose(self.image_diff_mode, self.image_diff_position);
+                        self.image_diff = Some(image_diff);
+                    }
+                    ChangeDiff::Text(file_diff) => self.diff = Some(file_diff)
+                }
+                Task::none()
+            }
+            Interaction::SetImageDiffMode(mode) => {
+                self.image_diff_mode = mode;
+                if let Some(image_diff) = &mut self.image_diff {
+                    image_diff.compose(mode, self.image_diff_position);
+                }
+                Task::none()
+            }
+            Interaction::ImageDiffPosition(position) => {
+                self.image_diff_position = position;
+                if let Some(image_diff) = &mut self.image_diff {
+                    image_diff.compose(self.image_diff_mode, position);
+                }
+                Task::none()
+            }
+            Interaction::SetDiffMode(mode) => {
+                self.diff_mode = mode;
+                Task::none()
+            }
+            Interaction::CloseDiff => {
+                self.diff = None;
+                self.image_diff = None;
+                Task::none()
+            }
+            Interaction::EditFile => {
+                let Some(bytes) = &self.viewed_file_content else { return Task::none() };
+                if viewer::is_text(bytes) {
+                    self.file_editor = Some(text_editor::Content::with_text(&String::from_utf8_lossy(bytes)));
+                }
+                Task::none()
+            }
+            Interaction::FileEditorAction(action) => {
+                if let Some(editor) = &mut self.file_editor {
+                    editor.perform(action);
+                }
+                Task::none()
+            }
+            Interaction::SaveFile => {
+                let (Some(editor), Some(path), Some(original)) = (&self.file_editor, &self.viewed_file_path, &self.viewed_file_content) else {
+                    return Task::none()
+                };
+                let original = String::from_utf8_lossy(original).to_string();
+                let mut text = editor.text();
+                if !original.ends_with("\n") {
+                    text.pop();
+                }
+                if original.contains("\r\n") {
+                    text = text.replace("\n", "\r\n");
+                }
+                let path = path.clone();
+                let name = self.viewed_file_name.clone().unwrap_or_default();
+                self.file_editor = None;
+                if text == original {
+                    return Task::none();
+                }
+                let Some(content) = self.workspace_content.clone() else { panic!() };
+                if workspace::find_content(&content, &path).is_none() {
+                    self.notice = Some(format!("{} no longer exists, the edit was not saved.", path));
+                    return Task::none();
+                }
+                let bytes = text.into_bytes();
+                self.history.begin(format!("Edit {}", name), &content, &mut self.modification, &[path.clone()]);
+                self.modification.set(path.clone(), FileContent::Bytes(bytes.clone()));
+                self.history.commit(&content, &mut self.modification);
+                self.viewed_text = viewer::text_preview(&name, &bytes);
+                self.viewed_image = None;
+                self.viewed_file_content = Some(bytes.clone());
+                if viewer::sniff_image(&name, &bytes).is_none() {
+                    return Task::none();
+                }
+                self.start_loading(Loading::File);
+                Task::perform(viewer::decode_image(name, bytes), move |preview| Interaction::ReceiveImage(path.clone(), preview))
+            }
+            Interaction::CancelEdit => {
+                self.file_editor = None;
+                Task::none()
+            }
+            Interaction::SaveSettings => {
+                wrapper::set_stored_settings(&self.settings);
+                self.display = self.settings_origin.clone();
+                Task::none()
+            }
+            Interaction::SendChanges => {
+                if !self.modification_name.is_empty() {
+                    let crab = self.get_crab().clone();
+                    let workspace = self.selected_workspace.clone().unwrap();
+                    let pending = self.modification.pending();
+                    let mut paths = self.staged_paths(&pending);
+                    if paths.is_empty() {
+                        paths = pending.into_iter().map(|change| change.path).collect();
+                    }
+                    let modification = self.modification.clone().extract(&paths);
+                    self.sent_modification = Some(modification.clone());
+                    let commit_message = self.commit_message();
+                    let author = self.commit_author();
+                    self.start_loading(Loading::Sending);
+                    self.displayed_progress = 0.0;
+                    Task::run(workspace::send_contents(crab, workspace, modification, commit_message, author), Interaction::SendProgress)
+                }
+                else {
+                    Task::none()
+                }
+            }
+            Interaction::SendProgress(SendProgress::Advanced(completed, total)) => {
+                self.loading.insert(Loading::Sending, Some(completed as f32 / total as f32));
+                Task::none()
+            }
+            Interaction::SendProgress(SendProgress::Sent) => {
+                self.finish_loading(&Loading::Sending);
+                if let Some(sent) = self.sent_modification.take() {
+                    self.modification.settle(&sent);
+                }
+                Task::done(Interaction::ReloadWorkspace)
+            }
+            Interaction::SendProgress(SendProgress::Failed(error)) => {
+                self.finish_loading(&Loading::Sending);
+                self.sent_modification = None;
+                self.notice = Some(format!("The changes could not be sent: {}", error));
+                Task::none()
+            }
+            Interaction::ReloadWorkspace => {
+                self.refactors.clear();
+                self.creation = None;
+                self.duplicates.clear();
+                self.staged_changes.clear();
+                self.modification_name = "".to_string();
+                self.commit_description = text_editor::Content::new();
+                self.co_authors.clear();
+                self.start_loading(Loading::WorkspaceContent);
+                let crab = self.get_crab();
+                let workspace = self.selected_workspace.clone().unwrap();
+                Task::perform(workspace::get_workspace_content(crab.clone(), workspace), |(x, y, z)| Interaction::ReceiveWorkspaceContent(x, y, z))
+            }
+            Interaction::ResetChanges => {
+                self.show_pending_changes = false;
+                self.refactors.clear();
+                self.creation = None;
+                self.duplicates.clear();
+                self.staged_changes.clear();
+                self.modification.reset();
+                self.history.clear();
+                self.modification_name = "".to_string();
+                self.commit_description = text_editor::Content::new();
+                self.co_authors.clear();
+                self.start_loading(Loading::WorkspaceContent);
+                let crab = self.get_crab();
+                let workspace = self.selected_workspace.clone().unwrap();
+                Task::perform(workspace::get_workspace_content(crab.clone(), workspace), |(x, y, z)| Interaction::ReceiveWorkspaceContent(x, y, z))
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Interaction, Theme, Renderer> {
+        match &self.display {
+            Display::GithubConnexion => self.github_connection(),
+            Display::ProjectSelection => self.project_selection(),
+            Display::WorkspaceCreation => self.workspace_creation(),
+            Display::WorkspaceSelection => self.workspace_selection(),
+            Display::WorkspaceContent => self.workspace_content(),
+            Display::Settings => self.settings()
+        }
+    }
+
+    fn github_connection(&self) -> Element<'_, Interaction, Theme, Renderer> {
+        let illusionna_title = Image::new(image::Handle::from_bytes(include_bytes!("../resources/title.png").as_slice()))
+            .filter_method(FilterMethod::Nearest)
+            .width(Length::Fixed(426f32))
+            .height(Length::Fixed(240f32));
         let device_auth_text = text("GitHub Authentication");
         let device_auth_button = Button::new("Login to GitHub via Device Flow")
             .style(small_button)
@@ -624,7 +2286,11 @@ impl IllusionnaApp {
                                 .padding(10)
                                 .style(large_button)
                                 .on_press(Interaction::OpenLink(info.clone().profile));
-                            Column::new().push(button)
+                            Column::new()
+                                .push(Button::new("Settings").style(small_button).on_press(Interaction::OpenSettings))
+                                .push(button)
+                                .spacing(5)
+                                .align_x(Horizontal::Right)
                         }
                         None => Column::new()
                     }).align_right(Length::Fill).align_bottom(Length::Fill));
@@ -632,14 +2298,7 @@ impl IllusionnaApp {
                     .push(scroll)
                     .push(Column::new().push(project_info).push(creation_and_account)).into()
             }
-            None => {
-                Container::new(
-                    Image::new(image::Handle::from_bytes(ICON))
-                        .width(Length::Fixed(48f32))
-                        .height(Length::Fixed(48f32))
-                        .rotation(Rotation::Floating(Radians::from(Degrees(self.rotator as f32))))
-                ).center(Length::Fill).into()
-            }
+            None => Container::new(self.loading_icon(48f32)).center(Length::Fill).into()
         }
     }
 
@@ -656,7 +2315,7 @@ impl IllusionnaApp {
         }
         let selected_project = self.selected_project.clone().unwrap();
         let workspaces_widget = match &self.workspaces {
-            Some(workspaces) => {
+            Some(workspaces) if !self.is_loading(&Loading::WorkspaceContent) => {
                 if !workspaces.is_empty() {
                     let iterations = workspaces.len().div_euclid(2);
                     let mut column = vec![];
@@ -683,243 +2342,1074 @@ impl IllusionnaApp {
                 }
                 else {
                     Column::new()
-                }
-            }
-            None => Column::new().push(
-                Container::new(
-                    Image::new(image::Handle::from_bytes(ICON))
-                        .width(Length::Fixed(32f32))
-                        .height(Length::Fixed(32f32))
-                        .rotation(Rotation::Floating(Radians::from(Degrees(self.rotator as f32))))
-                ).center_x(Length::Fill).center_y(288f32)
+                }
+            }
+            _ => Column::new().push(
+                Container::new(self.loading_icon(32f32)).center_x(Length::Fill).center_y(288f32)
+            )
+        };
+        Column::new()
+            .push(
+                Row::new()
+                    .push(
+                        Row::new()
+                            .push(Image::new(selected_project.source_owner_icon).width(Length::Fixed(64f32)).height(Length::Fixed(64f32)))
+                            .push(Text::new(selected_project.source_owner).size(28))
+                            .spacing(10)
+                            .align_y(Vertical::Center)
+                    )
+                    .push(
+                        Text::new(format!("{} ({})", selected_project.fork_name, selected_project.source_name)).size(28).width(Length::Fill).align_x(Horizontal::Right)
+                    )
+                    .padding(25)
+                    .align_y(Vertical::Center)
+            )
+            .push(scrollable(workspaces_widget).height(Length::Fixed(288f32)))
+            .push(
+                Container::new(
+                    Row::new()
+                        .push(
+                            Column::new()
+                                .push(Button::new(Text::new("Return back to Projects List")).style(small_button).on_press(Interaction::DisplayProjectsList))
+                                .width(Length::Fill)
+                                .align_x(Horizontal::Left)
+                        )
+                        .push(
+                            Column::new()
+                                .push(Checkbox::new("Display Closed Workspaces", self.show_closed).on_toggle(Interaction::ToggleClosedWorkspaces))
+                                .width(Length::Fill)
+                                .align_x(Horizontal::Center)
+                        )
+                        .push(
+                            Column::new()
+                                .push(Button::new(Text::new("Create New Workspace")).style(small_button).on_press(Interaction::CreateNewWorkspace))
+                                .width(Length::Fill)
+                                .align_x(Horizontal::Right)
+                        )
+                        .align_y(Vertical::Center)
+                ).align_bottom(Length::Fill).padding(10)
+            )
+            .into()
+    }
+
+    fn workspace_creation(&self) -> Element<'_, Interaction, Theme, Renderer> {
+        let selected_project = self.selected_project.clone().unwrap();
+        Column::new()
+            .push(
+                Row::new()
+                    .push(Image::new(selected_project.source_owner_icon).width(Length::Fixed(64f32)).height(Length::Fixed(64f32)))
+                    .push(
+                        Column::new()
+                            .push(Text::new("Workspace Creation").size(24))
+                            .push(Text::new(format!("{}/{}", selected_project.source_owner, selected_project.source_name)))
+                    )
+                    .spacing(10)
+                    .align_y(Vertical::Center)
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("Workspace Name"))
+                    .push(TextInput::new("Enter a title", &self.workspace_creation_name_text).width(Length::Fixed(250f32)).on_input(Interaction::WorkspaceNameInput))
+                    .spacing(6)
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("Workspace Id"))
+                    .push(TextInput::new("Only [a-z] & [0-9] & \"-\" & \"/\"", &self.workspace_creation_id_text).width(Length::Fixed(250f32)).on_input(Interaction::WorkspaceIdInput))
+                    .spacing(6)
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("Workspace Description"))
+                    .push(TextInput::new("Optionally write a description", &self.workspace_creation_description_text).width(Length::Fixed(250f32)).on_input(Interaction::WorkspaceDescriptionInput))
+                    .spacing(6)
+            )
+            .push(
+                Button::new(Text::new("Create Workspace"))
+                    .style(small_button)
+                    .on_press(Interaction::ProcessNewWorkspace)
+            )
+            .push(
+                Button::new(Text::new("Back to Workspaces List"))
+                    .style(small_button)
+                    .on_press(Interaction::DisplayWorkspacesList)
+            )
+            .padding(25)
+            .spacing(25)
+            .width(Length::Fill)
+            .align_x(Horizontal::Center)
+            .into()
+    }
+
+    fn settings(&self) -> Element<'_, Interaction, Theme, Renderer> {
+        let identity = self.account.as_ref().map(|account| account.identity.clone());
+        let email_valid = self.settings.author_email.is_empty() || wrapper::is_valid_email(&self.settings.author_email);
+        Column::new()
+            .push(Text::new("Settings").size(24))
+            .push(
+                Column::new()
+                    .push(Text::new("Commit Author Name"))
+                    .push(
+                        TextInput::new(&identity.as_ref().map(|identity| identity.name.clone()).unwrap_or("From GitHub Profile".to_string()), &self.settings.author_name)
+                            .width(Length::Fixed(300f32))
+                            .on_input(Interaction::AuthorNameInput)
+                    )
+                    .spacing(6)
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("Commit Author Email"))
+                    .push(
+                        TextInput::new(&identity.as_ref().map(|identity| identity.email.clone()).unwrap_or("From GitHub Profile".to_string()), &self.settings.author_email)
+                            .width(Length::Fixed(300f32))
+                            .on_input(Interaction::AuthorEmailInput)
+                    )
+                    .push_maybe((!email_valid).then(|| Text::new("This is not a valid email address.").size(12).color(Color::from_rgb8(230, 170, 40))))
+                    .spacing(6)
+            )
+            .push(Checkbox::new("Record Illusionna Version in Commits", self.settings.record_version).on_toggle(Interaction::ToggleVersionTrailer))
+            .push(
+                Column::new()
+                    .push(Text::new("Additional Commit Trailer"))
+                    .push(
+                        TextInput::new("Key: Value", &self.settings.custom_trailer)
+                            .width(Length::Fixed(300f32))
+                            .on_input(Interaction::CustomTrailerInput)
+                    )
+                    .spacing(6)
+            )
+            .push(
+                Column::new()
+                    .push(Text::new("Ignored Patterns"))
+                    .push(
+                        TextEditor::new(&self.ignore_patterns)
+                            .placeholder("One .gitignore pattern per line")
+                            .height(Length::Fixed(72f32))
+                            .on_action(Interaction::IgnorePatternsAction)
+                            .key_binding(editor_binding)
+                    )
+                    .width(Length::Fixed(300f32))
+                    .spacing(6)
+            )
+            .push(
+                Row::new()
+                    .push(Button::new("Save Settings").style(small_button).on_press_maybe(email_valid.then_some(Interaction::SaveSettings)))
+                    .push(Button::new("Cancel").style(small_button).on_press(Interaction::CloseSettings))
+                    .spacing(10)
+            )
+            .padding(25)
+            .spacing(20)
+            .width(Length::Fill)
+            .align_x(Horizontal::Center)
+            .into()
+    }
+
+    fn creation_input(&self) -> Element<'_, Interaction> {
+        let Some(creation) = &self.creation else { return Row::new().into() };
+        TextInput::new(if creation.folder { "New Folder Name" } else { "New File Name" }, &creation.input)
+            .on_input(Interaction::CreationInput)
+            .on_submit(Interaction::PushCreation)
+            .into()
+    }
+
+    fn display_row<'a>(&'a self, row: &'a TreeRow) -> Option<Element<'a, Interaction>> {
+        let value = workspace::find_content(self.workspace_content.as_ref()?, &row.path)?;
+        let indentation = 15.0 * (row.depth + 1) as f32;
+        let is_dir = matches!(&value.content, PathContent::Directory(_));
+        let modifier = Button::new(
+            Svg::new(svg::Handle::from_memory(if is_dir { APPEND } else { REPLACE })).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+        ).style(small_button).on_press(Interaction::SelectFiles(is_dir, value.path.clone()));
+        let import = is_dir.then(|| Button::new(
+            Svg::new(svg::Handle::from_memory(IMPORT)).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+        ).style(small_button).on_press(Interaction::SelectFolder(value.path.clone())));
+        let new_folder = is_dir.then(|| Button::new(
+            Svg::new(svg::Handle::from_memory(NEW_FOLDER)).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+        ).style(small_button).on_press(Interaction::CreateEntry(value.path.clone(), true)));
+        let new_file = is_dir.then(|| Button::new(
+            Svg::new(svg::Handle::from_memory(NEW_FILE)).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+        ).style(small_button).on_press(Interaction::CreateEntry(value.path.clone(), false)));
+        let duplicate = Button::new(
+            Svg::new(svg::Handle::from_memory(DUPLICATE)).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+        ).style(small_button).on_press(Interaction::DuplicateFiles(value.path.clone()));
+        let rename = Button::new(
+            Svg::new(svg::Handle::from_memory(RENAME)).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(0, 255, 0), t, s))
+        ).style(small_button).on_press(Interaction::RefactorFiles(value.path.clone()));
+        let remove = Button::new(
+            Svg::new(svg::Handle::from_memory(REMOVE)).width(Length::Fixed(16f32))
+                .style(|t, s| advanced_svg(Color::from_rgb8(255, 0, 0), t, s))
+        ).style(small_button).on_press(Interaction::EraseFiles(value.path.clone()));
+        let operations = Container::new(
+            Row::new()
+                .push(modifier).push_maybe(import).push_maybe(new_folder).push_maybe(new_file).push(duplicate).push(rename).push(remove)
+                .spacing(2.5)
+                .align_y(Vertical::Center)
+        ).padding(Padding::new(0.0).right(2.5)).center_y(Length::Fill).align_right(Length::Fill);
+        let element: Element<'a, Interaction> = match &value.content {
+            _ if self.duplicates.contains_key(&value.path) => {
+                TextInput::new("Duplicate As", self.duplicates.get(&value.path).unwrap())
+                    .on_input(|input| Interaction::DuplicateInput(value.path.to_string(), input))
+                    .on_submit(Interaction::PushDuplicate(value.path.to_string()))
+                    .into()
+            }
+            PathContent::File(info) => {
+                if !self.refactors.contains_key(&value.path) {
+                    let icon = match value.mode {
+                        EntryMode::Executable => Some(EXECUTABLE),
+                        EntryMode::Symlink => Some(SYMLINK),
+                        EntryMode::Submodule => Some(SUBMODULE),
+                        _ => None
+                    };
+                    let mut label = Row::new()
+                        .push_maybe(icon.map(|icon| Svg::new(svg::Handle::from_memory(icon)).width(Length::Fixed(16f32)).style(default_svg)))
+                        .spacing(5)
+                        .align_y(Vertical::Center);
+                    label = match self.modification.status(&value.path) {
+                        Some(kind) => label
+                            .push(Text::new(&info.name).color(change_color(kind)))
+                            .push(Text::new(change_marker(kind)).size(10).color(change_color(kind))),
+                        None => label.push(Text::new(&info.name))
+                    };
+                    if value.mode == EntryMode::Submodule {
+                        Button::new(label.push(Text::new(format!("@ {}", &value.sha[..value.sha.len().min(7)])).size(10)))
+                            .padding(Padding::new(3.0).left(9.0))
+                            .width(Length::Fill)
+                            .style(small_button)
+                            .into()
+                    }
+                    else {
+                        widget::hover(
+                            Button::new(label)
+                                .padding(Padding::new(3.0).left(9.0))
+                                .width(Length::Fill)
+                                .style(small_button)
+                                .on_press(Interaction::ClickRow((&value).path.to_string(), Some((&value.sha).to_string()))),
+                            operations
+                        )
+                    }
+                } else {
+                    TextInput::new("Rename File", self.refactors.get(&value.path).unwrap())
+                        .on_input(|input| Interaction::RefactorInput(value.path.to_string(), input))
+                        .on_submit(Interaction::PushRefactor(value.path.to_string(), value.sha.to_string()))
+                        .into()
+                }
+            }
+            PathContent::Directory(_) if self.creation.as_ref().is_some_and(|creation| creation.directory == value.path) => {
+                self.creation_input()
+            }
+            PathContent::Directory(info) => {
+                if !self.refactors.contains_key(&value.path) {
+                    let collapsed = self.collapsed_directories.contains(&value.path) || !info.loaded;
+                    let expandable = info.loaded || self.modification.can_load(&value.path);
+                    let management_button: Element<'a, Interaction> = if self.is_loading(&Loading::Directory(value.path.clone())) {
+                        Container::new(self.loading_icon(16f32)).padding(3).into()
+                    } else if collapsed {
+                        Button::new(Svg::new(svg::Handle::from_memory(EXPAND)).width(Length::Fixed(16f32)).style(default_svg))
+                            .padding(3)
+                            .style(small_button)
+                            .on_press_maybe(expandable.then(|| Interaction::ExpandDirectory(value.path.to_string())))
+                            .into()
+                    } else {
+                        Button::new(Svg::new(svg::Handle::from_memory(COLLAPSE)).width(Length::Fixed(16f32)).style(default_svg))
+                            .padding(3)
+                            .style(small_button)
+                            .on_press(Interaction::CollapseDirectory(value.path.to_string()))
+                            .into()
+                    };
+                    widget::hover(
+                        Button::new(
+                            Row::new()
+                                .push(management_button)
+                                .push(Svg::new(svg::Handle::from_memory(FOLDER)).width(Length::Fixed(16f32)).style(default_svg))
+                                .push(Text::new(&info.name))
+                                .push_maybe(self.modification.touches(&value.path).then(|| Text::new("\u{2022}").color(Color::from_rgb8(72, 68, 255))))
+                                .spacing(5)
+                                .align_y(Vertical::Center)
+                        ).width(Length::Fill).padding(3).style(small_button).on_press(Interaction::ClickRow(value.path.to_string(), None)),
+                        operations
+                    )
+                } else {
+                    TextInput::new("Rename Directory", self.refactors.get(&value.path).unwrap())
+                        .on_input(|input| Interaction::RefactorInput(value.path.to_string(), input))
+                        .on_submit(Interaction::PushRefactor(value.path.to_string(), value.sha.to_string()))
+                        .into()
+                }
+            }
+        };
+        let target = if is_dir {
+            value.path.clone()
+        } else {
+            value.path.rsplit_once("/").map(|(parent, _)| parent.to_string()).unwrap_or_default()
+        };
+        let highlighted = is_dir && (self.files_hovered || self.dragged.is_some()) && self.drop_target == target;
+        let selected = self.selection.contains(&value.path);
+        let handle: Option<Element<'a, Interaction>> = (value.mode != EntryMode::Submodule).then(|| {
+            mouse_area(Svg::new(svg::Handle::from_memory(GRIP)).width(Length::Fixed(12f32)).style(default_svg))
+                .on_press(Interaction::StartDrag(value.path.clone()))
+                .interaction(mouse::Interaction::Grab)
+                .into()
+        });
+        Some(
+            mouse_area(
+                Container::new(Row::new().push_maybe(handle).push(element).spacing(2).align_y(Vertical::Center))
+                    .width(Length::Fixed(350f32))
+                    .padding(Padding::new(2.5).left(if value.mode == EntryMode::Submodule { 16.5 + indentation } else { 2.5 + indentation }))
+                    .center_y(Length::Fixed(TREE_ROW_HEIGHT))
+                    .style(move |theme| tree_row(theme, highlighted, selected))
+            ).on_enter(Interaction::HoverDropTarget(target)).into()
+        )
+    }
+
+    /// Builds the rows of the workspace tree that are inside the scrolled viewport, surrounded by
+    /// spaces standing for the rows that are not.
+    fn display_content(&self) -> Vec<Element<'_, Interaction>> {
+        let (offset, height) = self.workspace_scroll;
+        let height = if height > 0.0 { height } else { 1080.0 };
+        let total = self.workspace_rows.len();
+        let first = ((offset - TREE_PADDING - TREE_HEADER_HEIGHT).max(0.0) / TREE_ROW_HEIGHT) as usize;
+        let start = first.saturating_sub(TREE_OVERSCAN).min(total);
+        let end = (first + (height / TREE_ROW_HEIGHT).ceil() as usize + TREE_OVERSCAN).min(total);
+        let mut vec: Vec<Element<'_, Interaction>> = vec![Space::with_height(start as f32 * TREE_ROW_HEIGHT).into()];
+        vec.extend(self.workspace_rows[start..end].iter().filter_map(|row| self.display_row(row)));
+        vec.push(Space::with_height((total - end) as f32 * TREE_ROW_HEIGHT).into());
+        vec
+    }
+
+    fn image_preview<'a>(&'a self, preview: &'a ImagePreview) -> Element<'a, Interaction, Theme, Renderer> {
+        let gray = Color::from_rgb8(128, 128, 128);
+        let path = self.viewed_file_path.clone().unwrap_or_default();
+        let display: Element<Interaction, Theme, Renderer> = match preview {
+            ImagePreview::Vector(handle) => Svg::new(handle.clone())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into(),
+            ImagePreview::Raster { .. } => Viewer::new(match &self.texture_animation {
+                Some(animation) => animation.current.clone(),
+                None => preview.frame_at(self.animation_elapsed).unwrap().clone()
+            })
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .filter_method(FilterMethod::Nearest)
+                .scale_step(0.5)
+                .into()
+        };
+        let size = self.viewed_file_content.as_ref().map(|bytes| bytes.len()).unwrap_or_default();
+        let mut details = vec![];
+        let mut mismatch = None;
+        match preview {
+            ImagePreview::Raster { metadata, frames } => {
+                details.push(metadata.format.to_string());
+                details.push(format!("{}x{}", metadata.width, metadata.height));
+                details.push(format!("{}-bit {}", metadata.bit_depth, metadata.color_type));
+                details.push(match (metadata.alpha, metadata.translucent) {
+                    (true, true) => "Transparent".to_string(),
+                    (true, false) => "Alpha (Opaque)".to_string(),
+                    (false, _) => "No Alpha".to_string()
+                });
+                if frames.len() > 1 {
+                    details.push(format!("{} Frames", frames.len()));
+                }
+                mismatch = self.upstream_dimensions.filter(|dimensions| *dimensions != (metadata.width, metadata.height));
+            }
+            ImagePreview::Vector(_) => details.push("SVG".to_string())
+        }
+        details.push(format_size(size as u64));
+        let compare = matches!(self.modification.status(&path), Some(ChangeKind::Replaced | ChangeKind::Renamed)).then(|| {
+            Button::new(Text::new("Compare with Upstream").size(12)).style(small_button).on_press(Interaction::ViewDiff(path))
+        });
+        let strip = Row::new()
+            .push(Text::new(details.join("  \u{00b7}  ")).size(12).color(gray).width(Length::Fill))
+            .push_maybe(compare)
+            .align_y(Vertical::Center);
+        let controls = self.texture_animation.as_ref().map(|animation| {
+            let (position, _) = animation.position();
+            Row::new()
+                .push(Button::new(Text::new("\u{25c0}").size(12)).style(small_button).on_press(Interaction::StepAnimation(false)))
+                .push(Button::new(Text::new(if self.animation_paused { "Play" } else { "Pause" }).size(12)).style(small_button).on_press(Interaction::ToggleAnimation))
+                .push(Button::new(Text::new("\u{25b6}").size(12)).style(small_button).on_press(Interaction::StepAnimation(true)))
+                .push(Text::new(format!("Frame {}/{} (.mcmeta), Tick {}", position + 1, animation.frame_count(), animation.tick())).size(12).color(gray))
+                .spacing(5)
+                .align_y(Vertical::Center)
+        });
+        Column::new()
+            .push(display)
+            .push_maybe(controls)
+            .push_maybe(mismatch.map(|(width, height)| {
+                Text::new(format!("The upstream image is {}x{}, this replacement has different dimensions.", width, height))
+                    .size(12)
+                    .color(Color::from_rgb8(230, 170, 40))
+            }))
+            .push(strip)
+            .spacing(6)
+            .into()
+    }
+
+    fn audio_preview<'a>(&'a self, clip: &'a AudioClip) -> Element<'a, Interaction, Theme, Renderer> {
+        let gray = Color::from_rgb8(128, 128, 128);
+        let playing = self.audio_player.as_ref().is_some_and(|player| player.is_playing());
+        let position = self.audio_player.as_ref().map(|player| player.position()).unwrap_or_default().min(clip.duration);
+        let played = (position.as_secs_f32() / clip.duration.as_secs_f32().max(f32::EPSILON) * 1000.0) as u16;
+        let playhead = Row::new()
+            .push(Space::with_width(Length::FillPortion(played)))
+            .push(Container::new(Space::new(Length::Fixed(2.0), Length::Fill)).style(|_| container::Style {
+                background: Some(Background::Color(Color::WHITE)),
+                ..container::Style::default()
+            }))
+            .push(Space::with_width(Length::FillPortion(1000 - played.min(1000))));
+        let waveform = Stack::new()
+            .push(Image::new(clip.waveform.clone()).width(Length::Fill).height(Length::Fill).content_fit(ContentFit::Fill))
+            .push(playhead)
+            .width(Length::Fill)
+            .height(Length::Fill);
+        let controls = Row::new()
+            .push(Button::new(Text::new(if playing { "Pause" } else { "Play" }).size(12)).style(small_button).on_press(Interaction::ToggleAudio))
+            .push(Slider::new(0.0..=clip.duration.as_secs_f32(), position.as_secs_f32(), Interaction::SeekAudio).step(0.01))
+            .push(Text::new(format!("{} / {}", format_duration(position), format_duration(clip.duration))).size(12).color(gray))
+            .spacing(10)
+            .align_y(Vertical::Center);
+        let size = self.viewed_file_content.as_ref().map(|bytes| bytes.len()).unwrap_or_default();
+        let details = [
+            clip.codec.clone(),
+            format!("{} Hz", clip.sample_rate),
+            clip.channel_name(),
+            format_duration(clip.duration),
+            format_size(size as u64)
+        ];
+        Column::new()
+            .push(waveform)
+            .push(controls)
+            .push(Text::new(details.join("  \u{00b7}  ")).size(12).color(gray))
+            .spacing(6)
+            .into()
+    }
+
+    fn font_preview<'a>(&'a self, metadata: &'a FontMetadata) -> Element<'a, Interaction, Theme, Renderer> {
+        let gray = Color::from_rgb8(128, 128, 128);
+        let warning = Color::from_rgb8(230, 170, 40);
+        let size = self.viewed_file_content.as_ref().map(|bytes| bytes.len()).unwrap_or_default();
+        let mut details = vec![];
+        details.extend(metadata.subfamily.clone());
+        details.push(metadata.outlines.to_string());
+        details.push(format!("Weight {}", metadata.weight));
+        if metadata.italic {
+            details.push("Italic".to_string());
+        }
+        if metadata.monospaced {
+            details.push("Monospaced".to_string());
+        }
+        if metadata.variable {
+            details.push("Variable".to_string());
+        }
+        details.push(format!("{} Glyphs", metadata.glyph_count));
+        details.extend(metadata.version.clone());
+        details.push(format_size(size as u64));
+        let header = Column::new()
+            .push(Text::new(&metadata.family).size(24).font(self.viewed_font_face.unwrap_or_default()))
+            .push(Text::new(details.join("  \u{00b7}  ")).size(12).color(gray));
+        let specimen: Element<Interaction, Theme, Renderer> = match self.viewed_font_face {
+            Some(face) => FONT_SPECIMEN_SIZES.iter().fold(Column::new().spacing(8), |column, size| {
+                column.push(
+                    Row::new()
+                        .push(Text::new(format!("{}", size)).size(12).color(gray).width(Length::Fixed(30f32)))
+                        .push(Text::new(&self.font_sample).font(face).size(*size))
+                        .spacing(10)
+                        .align_y(Vertical::Center)
+                )
+            })
+                .push(Text::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ\nabcdefghijklmnopqrstuvwxyz\n0123456789 !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~").font(face).size(20))
+                .into(),
+            None if self.viewed_font_conflict => Text::new(format!(
+                "Another font named \"{}\" with the same weight and style is already loaded, either by Illusionna itself or from another file. \
+                It would be shown instead of this one, so no specimen can be displayed until Illusionna is restarted.",
+                metadata.family
+            )).size(12).color(warning).into(),
+            None => Container::new(self.loading_icon(32f32)).center_x(Length::Fill).into()
+        };
+        let coverage = metadata.coverage.iter().fold(Column::new().spacing(2), |column, block| {
+            let color = match block.covered {
+                0 => gray,
+                covered if covered == block.total => Color::from_rgb8(80, 180, 100),
+                _ => warning
+            };
+            column.push(
+                Row::new()
+                    .push(Text::new(block.name).size(12).width(Length::Fill))
+                    .push(Text::new(format!("{}/{}", block.covered, block.total)).size(12).color(color))
+            )
+        });
+        let missing = (!metadata.missing_ascii.is_empty()).then(|| {
+            Text::new(format!("Missing ASCII characters: {}", metadata.missing_ascii)).size(12).color(warning)
+        });
+        Column::new()
+            .push(header)
+            .push(TextInput::new("Sample Text", &self.font_sample).on_input(Interaction::UpdateFontSample).size(12))
+            .push(
+                Scrollable::new(
+                    Column::new()
+                        .push(specimen)
+                        .push(Text::new("Glyph Coverage").size(16))
+                        .push(coverage)
+                        .push_maybe(missing)
+                        .spacing(10)
+                        .padding(Padding::new(0f32).right(15))
+                ).height(Length::Fill)
             )
+            .spacing(10)
+            .into()
+    }
+
+    /// The font the renderer knows a face by. Fonts are referred to by a static name, so each
+    /// family name is only leaked once.
+    fn font_face(&mut self, metadata: &FontMetadata) -> Font {
+        let family = match self.font_family_names.get(metadata.family.as_str()) {
+            Some(family) => *family,
+            None => {
+                let family: &'static str = Box::leak(metadata.family.clone().into_boxed_str());
+                self.font_family_names.insert(family);
+                family
+            }
         };
+        Font {
+            family: font::Family::Name(family),
+            weight: font_weight(metadata.weight),
+            style: if metadata.italic { font::Style::Italic } else { font::Style::Normal },
+            ..Font::DEFAULT
+        }
+    }
+
+    fn file_editor<'a>(&'a self, editor: &'a text_editor::Content) -> Element<'a, Interaction, Theme, Renderer> {
+        let name = self.viewed_file_name.clone().unwrap_or_default();
+        let symlink = self.viewed_file_path.as_ref().is_some_and(|path| self.modification.is_symlink(path));
+        let header = Row::new()
+            .push(Text::new(format!("Editing {}", name)).size(14).color(Color::from_rgb8(128, 128, 128)).width(Length::Fill))
+            .push_maybe(symlink.then(|| Text::new("This is a symbolic link, saving turns it into a regular file.").size(12).color(Color::from_rgb8(230, 170, 40))))
+            .push(Button::new("Cancel").style(small_button).on_press(Interaction::CancelEdit))
+            .push(Button::new("Save").style(small_button).on_press(Interaction::SaveFile))
+            .spacing(10)
+            .align_y(Vertical::Center);
         Column::new()
+            .push(header)
             .push(
-                Row::new()
-                    .push(
-                        Row::new()
-                            .push(Image::new(selected_project.source_owner_icon).width(Length::Fixed(64f32)).height(Length::Fixed(64f32)))
-                            .push(Text::new(selected_project.source_owner).size(28))
-                            .spacing(10)
-                            .align_y(Vertical::Center)
-                    )
-                    .push(
-                        Text::new(format!("{} ({})", selected_project.fork_name, selected_project.source_name)).size(28).width(Length::Fill).align_x(Horizontal::Right)
-                    )
-                    .padding(25)
-                    .align_y(Vertical::Center)
+                TextEditor::new(editor)
+                    .on_action(Interaction::FileEditorAction)
+                    .highlight(&viewer::syntax_token(&name), iced::highlighter::Theme::Base16Ocean)
+                    .font(Font::MONOSPACE)
+                    .size(13)
+                    .height(Length::Fill)
             )
-            .push(scrollable(workspaces_widget).height(Length::Fixed(288f32)))
+            .spacing(10)
+            .into()
+    }
+
+    fn text_preview<'a>(&'a self, preview: &'a TextPreview<Interaction>) -> Element<'a, Interaction, Theme, Renderer> {
+        let lines = Row::new()
+            .push(Text::new(&preview.line_numbers).font(Font::MONOSPACE).size(13).color(Color::from_rgb8(128, 128, 128)).align_x(Horizontal::Right))
+            .push(rich_text(preview.spans.as_slice()).font(Font::MONOSPACE).size(13).wrapping(text::Wrapping::None))
+            .spacing(12);
+        let header = Row::new()
             .push(
-                Container::new(
-                    Row::new()
-                        .push(
-                            Column::new()
-                                .push(Button::new(Text::new("Return back to Projects List")).style(small_button).on_press(Interaction::DisplayProjectsList))
-                                .width(Length::Fill)
-                                .align_x(Horizontal::Left)
-                        )
-                        .push(
-                            Column::new()
-                                .push(Checkbox::new("Display Closed Workspaces", self.show_closed).on_toggle(Interaction::ToggleClosedWorkspaces))
-                                .width(Length::Fill)
-                                .align_x(Horizontal::Center)
-                        )
-                        .push(
-                            Column::new()
-                                .push(Button::new(Text::new("Create New Workspace")).style(small_button).on_press(Interaction::CreateNewWorkspace))
-                                .width(Length::Fill)
-                                .align_x(Horizontal::Right)
-                        )
-                        .align_y(Vertical::Center)
-                ).align_bottom(Length::Fill).padding(10)
+                Text::new(match preview.is_truncated() {
+                    true => format!("Showing the first {} of {} lines.", preview.shown_lines(), preview.total_lines),
+                    false => format!("{} lines", preview.total_lines)
+                }).size(14).color(Color::from_rgb8(128, 128, 128)).width(Length::Fill)
             )
+            .push(Button::new("Edit").style(small_button).on_press(Interaction::EditFile))
+            .align_y(Vertical::Center);
+        Column::new()
+            .push(header)
+            .push(
+                Scrollable::new(lines)
+                    .direction(scrollable::Direction::Both { vertical: scrollable::Scrollbar::default(), horizontal: scrollable::Scrollbar::default() })
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+            )
+            .spacing(10)
             .into()
     }
 
-    fn workspace_creation(&self) -> Element<'_, Interaction, Theme, Renderer> {
-        let selected_project = self.selected_project.clone().unwrap();
+    fn selection_panel(&self) -> Element<'_, Interaction, Theme, Renderer> {
+        let roots = self.selection_roots();
+        let title = Row::new()
+            .push(Text::new(format!("{} Selected Item(s)", roots.len())).size(20).width(Length::Fill))
+            .push(Button::new("Remove All").style(small_button).on_press(Interaction::DeleteSelection))
+            .push(Button::new("Clear Selection").style(small_button).on_press(Interaction::ClearSelection))
+            .spacing(10)
+            .align_y(Vertical::Center);
+        let movement = Row::new()
+            .push(TextInput::new("Destination Folder (empty for root)", &self.bulk_destination).on_input(Interaction::BulkDestinationInput).on_submit(Interaction::MoveSelection))
+            .push(Button::new("Move").style(small_button).on_press(Interaction::MoveSelection))
+            .spacing(10)
+            .align_y(Vertical::Center);
+        let renaming = Row::new()
+            .push(PickList::new(RenameMode::ALL, Some(self.rename_mode), Interaction::RenameModeSelected))
+            .push(TextInput::new(if self.rename_mode == RenameMode::Numbering { "name_##" } else { "Pattern" }, &self.rename_pattern).on_input(Interaction::RenamePatternInput))
+            .push_maybe((self.rename_mode == RenameMode::Regex).then(|| TextInput::new("Replacement ($1)", &self.rename_replacement).on_input(Interaction::RenameReplacementInput)))
+            .push(Button::new("Rename").style(small_button).on_press(Interaction::RenameSelection))
+            .spacing(10)
+            .align_y(Vertical::Center);
+        let entries: Vec<Element<Interaction, Theme, Renderer>> = match self.renamed_selection() {
+            Ok(renamed) => renamed.into_iter()
+                .map(|(path, name)| {
+                    let current = path.split("/").last().unwrap().to_string();
+                    let color = if current != name { change_color(ChangeKind::Renamed) } else { Color::from_rgb8(128, 128, 128) };
+                    Row::new()
+                        .push(Text::new(path).size(14).width(Length::Fill))
+                        .push(Text::new(name).size(14).color(color))
+                        .spacing(10)
+                        .into()
+                })
+                .collect(),
+            Err(reason) => vec![Text::new(reason).size(14).color(change_color(ChangeKind::Deleted)).into()]
+        };
         Column::new()
+            .push(title)
+            .push(movement)
+            .push(renaming)
+            .push(Scrollable::new(Column::new().extend(entries).spacing(4)).height(Length::Fill))
+            .spacing(10)
+            .into()
+    }
+
+    fn import_preview(&self) -> Element<'_, Interaction, Theme, Renderer> {
+        let Some(files) = &self.import_preview else { return Column::new().into() };
+        let replaced = files.keys().filter(|path| self.modification.mode(path).is_some()).count();
+        let title = Row::new()
             .push(
+                Text::new(format!("Importing {} File(s): {} Added, {} Replaced", files.len(), files.len() - replaced, replaced))
+                    .size(20)
+                    .width(Length::Fill)
+            )
+            .push(Button::new("Apply").style(small_button).on_press(Interaction::ApplyImport))
+            .push(Button::new("Cancel").style(small_button).on_press(Interaction::CancelImport))
+            .spacing(10)
+            .align_y(Vertical::Center);
+        let entries = files.iter().map(|(path, bytes)| {
+            let kind = if self.modification.mode(path).is_some() { ChangeKind::Replaced } else { ChangeKind::Added };
+            let color = change_color(kind);
+            Container::new(
                 Row::new()
-                    .push(Image::new(selected_project.source_owner_icon).width(Length::Fixed(64f32)).height(Length::Fixed(64f32)))
+                    .push(
+                        Svg::new(svg::Handle::from_memory(if kind == ChangeKind::Added { APPEND } else { REPLACE }))
+                            .width(Length::Fixed(16f32))
+                            .style(move |_, _| svg::Style { color: Some(color) })
+                    )
                     .push(
                         Column::new()
-                            .push(Text::new("Workspace Creation").size(24))
-                            .push(Text::new(format!("{}/{}", selected_project.source_owner, selected_project.source_name)))
+                            .push(Text::new(path).size(14))
+                            .push(Text::new(format!("{:?} - {}", kind, format_size(bytes.len() as u64))).size(10).color(color))
+                            .width(Length::Fill)
+                            .spacing(2)
                     )
-                    .spacing(10)
+                    .spacing(8)
                     .align_y(Vertical::Center)
-            )
+            ).padding(4).into()
+        }).collect::<Vec<Element<Interaction, Theme, Renderer>>>();
+        Column::new()
+            .push(title)
+            .push(Scrollable::new(Column::new().extend(entries).spacing(2)).height(Length::Fill))
+            .spacing(10)
+            .into()
+    }
+
+    fn image_diff<'a>(&'a self, image_diff: &'a ImageDiff) -> Element<'a, Interaction, Theme, Renderer> {
+        let gray = Color::from_rgb8(128, 128, 128);
+        let pixel_viewer = |handle: &image::Handle| Viewer::new(handle.clone())
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .filter_method(FilterMethod::Nearest)
+            .scale_step(0.5);
+        let title = Row::new()
             .push(
                 Column::new()
-                    .push(Text::new("Workspace Name"))
-                    .push(TextInput::new("Enter a title", &self.workspace_creation_name_text).width(Length::Fixed(250f32)).on_input(Interaction::WorkspaceNameInput))
-                    .spacing(6)
+                    .push(Text::new(&image_diff.path).size(16))
+                    .push(Text::new(format!(
+                        "{}x{} \u{2192} {}x{}, {} of {} pixel(s) changed",
+                        image_diff.old_size.0, image_diff.old_size.1, image_diff.new_size.0, image_diff.new_size.1, image_diff.changed, image_diff.total
+                    )).size(12).color(gray))
+                    .width(Length::Fill)
             )
+            .push(PickList::new(ImageDiffMode::ALL, Some(self.image_diff_mode), Interaction::SetImageDiffMode).text_size(12))
+            .push(Button::new(Text::new("Close").size(12)).style(small_button).on_press(Interaction::CloseDiff))
+            .spacing(5)
+            .align_y(Vertical::Center);
+        let comparison: Element<Interaction, Theme, Renderer> = match self.image_diff_mode {
+            ImageDiffMode::SideBySide => Row::new()
+                .push(Column::new().push(Text::new("Upstream").size(12).color(gray)).push(pixel_viewer(&image_diff.old)).spacing(4))
+                .push(Column::new().push(Text::new("Pending").size(12).color(gray)).push(pixel_viewer(&image_diff.new)).spacing(4))
+                .spacing(10)
+                .into(),
+            ImageDiffMode::Swipe | ImageDiffMode::Onion => Column::new()
+                .push(pixel_viewer(&image_diff.composite))
+                .push(
+                    Row::new()
+                        .push(Text::new("Upstream").size(12).color(gray))
+                        .push(Slider::new(0.0..=1.0, self.image_diff_position, Interaction::ImageDiffPosition).step(0.01))
+                        .push(Text::new("Pending").size(12).color(gray))
+                        .spacing(10)
+                        .align_y(Vertical::Center)
+                )
+                .spacing(10)
+                .into(),
+            ImageDiffMode::Heatmap => pixel_viewer(&image_diff.heatmap).into()
+        };
+        Column::new()
+            .push(title)
+            .push(comparison)
+            .spacing(10)
+            .into()
+    }
+
+    fn file_diff<'a>(&'a self, diff: &'a FileDiff) -> Element<'a, Interaction, Theme, Renderer> {
+        let gray = Color::from_rgb8(128, 128, 128);
+        let summary = match (self.modification.status(&diff.path), diff.binary) {
+            (_, Some((old, new))) => format!("Binary file, {} \u{2192} {}", format_size(old as u64), format_size(new as u64)),
+            (Some(ChangeKind::Added), _) => format!("New file, {} line(s)", diff.added),
+            (Some(ChangeKind::Deleted), _) => format!("Deleted file, {} line(s)", diff.removed),
+            _ => format!("+{} -{}", diff.added, diff.removed)
+        };
+        let mode = |label, mode| Button::new(Text::new(label).size(12))
+            .style(small_button)
+            .on_press_maybe((self.diff_mode != mode).then_some(Interaction::SetDiffMode(mode)));
+        let title = Row::new()
             .push(
                 Column::new()
-                    .push(Text::new("Workspace Id"))
-                    .push(TextInput::new("Only [a-z] & [0-9] & \"-\" & \"/\"", &self.workspace_creation_id_text).width(Length::Fixed(250f32)).on_input(Interaction::WorkspaceIdInput))
-                    .spacing(6)
+                    .push(Text::new(&diff.path).size(16))
+                    .push(Text::new(summary).size(12).color(gray))
+                    .width(Length::Fill)
             )
+            .push(mode("Unified", DiffMode::Unified))
+            .push(mode("Side by Side", DiffMode::SideBySide))
+            .push(Button::new(Text::new("Close").size(12)).style(small_button).on_press(Interaction::CloseDiff))
+            .spacing(5)
+            .align_y(Vertical::Center);
+        let mut rows: Vec<Element<Interaction, Theme, Renderer>> = vec![];
+        rows.extend(diff.notes.iter().map(|note| Text::new(note).size(14).color(gray).into()));
+        if diff.binary.is_none() && diff.hunks.is_empty() && diff.notes.is_empty() {
+            rows.push(Text::new("No textual differences.").size(14).color(gray).into());
+        }
+        let mut truncated = false;
+        for hunk in &diff.hunks {
+            if rows.len() >= MAX_DIFF_ROWS {
+                truncated = true;
+                break;
+            }
+            rows.push(Text::new(&hunk.header).font(Font::MONOSPACE).size(12).color(gray).into());
+            // A single hunk can be as long as the file, when it was added or deleted.
+            let remaining = MAX_DIFF_ROWS.saturating_sub(rows.len());
+            match self.diff_mode {
+                DiffMode::Unified => {
+                    truncated = hunk.lines.len() > remaining;
+                    rows.extend(hunk.lines.iter().take(remaining).map(|line| {
+                        let (marker, number) = match line.tag {
+                            DiffTag::Equal => (" ", line.new_number),
+                            DiffTag::Removed => ("-", line.old_number),
+                            DiffTag::Added => ("+", line.new_number)
+                        };
+                        diff_line(Some(line), format!("{:>5} {}", number.unwrap_or(0), marker))
+                    }))
+                }
+                DiffMode::SideBySide => {
+                    let pairs = diff::side_by_side(&hunk.lines);
+                    truncated = pairs.len() > remaining;
+                    rows.extend(pairs.into_iter().take(remaining).map(|(old, new)| {
+                        Row::new()
+                            .push(Container::new(diff_line(old.filter(|line| line.tag != DiffTag::Added), format!("{:>5}", old.and_then(|line| line.old_number).map(|n| n.to_string()).unwrap_or_default()))).width(Length::FillPortion(1)).clip(true))
+                            .push(Container::new(diff_line(new.filter(|line| line.tag != DiffTag::Removed), format!("{:>5}", new.and_then(|line| line.new_number).map(|n| n.to_string()).unwrap_or_default()))).width(Length::FillPortion(1)).clip(true))
+                            .spacing(4)
+                            .into()
+                    }))
+                }
+            }
+            if truncated {
+                break;
+            }
+        }
+        if truncated {
+            rows.push(Text::new("The remaining differences are too long to be displayed.").size(14).color(gray).into());
+        }
+        Column::new()
+            .push(title)
+            .push(Scrollable::new(Column::new().extend(rows).spacing(1)).height(Length::Fill))
+            .spacing(10)
+            .into()
+    }
+
+    fn pending_changes(&self, pending: &[PendingChange], staged: usize) -> Element<'_, Interaction, Theme, Renderer> {
+        let title = Row::new()
+            .push(Text::new(format!("{} Pending Change(s), {} Staged", pending.len(), staged)).size(20).width(Length::Fill))
             .push(
-                Column::new()
-                    .push(Text::new("Workspace Description"))
-                    .push(TextInput::new("Optionally write a description", &self.workspace_creation_description_text).width(Length::Fixed(250f32)).on_input(Interaction::WorkspaceDescriptionInput))
-                    .spacing(6)
+                Button::new(if staged == pending.len() { "Unstage All" } else { "Stage All" })
+                    .style(small_button)
+                    .on_press(Interaction::StageAllChanges(staged != pending.len()))
             )
+            .align_y(Vertical::Center);
+        let entries = pending.iter().map(|change| {
+            let color = change_color(change.kind);
+            let icon = match change.kind {
+                ChangeKind::Added => APPEND,
+                ChangeKind::Replaced => REPLACE,
+                ChangeKind::Renamed => RENAME,
+                ChangeKind::Deleted => REMOVE
+            };
+            let mut details = vec![format!("{:?}", change.kind)];
+            if let Some(origin) = &change.origin {
+                details.push(format!("from {}", origin));
+            }
+            if let Some(size) = change.size {
+                details.push(format_size(size));
+            }
+            let diff = Button::new(Svg::new(svg::Handle::from_memory(DIFF)).width(Length::Fixed(16f32)).style(default_svg))
+                .style(small_button)
+                .on_press(Interaction::ViewDiff(change.path.clone()));
+            let revert = Button::new(
+                Svg::new(svg::Handle::from_memory(UNDO)).width(Length::Fixed(16f32))
+                    .style(|t, s| advanced_svg(Color::from_rgb8(255, 0, 0), t, s))
+            ).style(small_button).on_press(Interaction::RevertChange(change.path.clone()));
+            let path = change.path.clone();
+            Container::new(
+                Row::new()
+                    .push(Checkbox::new("", self.staged_changes.contains(&change.path)).on_toggle(move |toggle| Interaction::StageChange(path.clone(), toggle)))
+                    .push(Svg::new(svg::Handle::from_memory(icon)).width(Length::Fixed(16f32)).style(move |_, _| svg::Style { color: Some(color) }))
+                    .push(
+                        Column::new()
+                            .push(Text::new(change.path.clone()).size(14))
+                            .push(Text::new(details.join(" - ")).size(10).color(color))
+                            .width(Length::Fill)
+                            .spacing(2)
+                    )
+                    .push(Tooltip::new(diff, Text::new("Show Differences").size(12), tooltip::Position::Left))
+                    .push(revert)
+                    .spacing(8)
+                    .align_y(Vertical::Center)
+            ).padding(4).into()
+        }).collect::<Vec<Element<Interaction, Theme, Renderer>>>();
+        Column::new()
+            .push(title)
+            .push(Scrollable::new(Column::new().extend(entries).spacing(2)).height(Length::Fill))
             .push(
-                Button::new(Text::new("Create Workspace"))
-                    .style(small_button)
-                    .on_press(Interaction::ProcessNewWorkspace)
+                TextEditor::new(&self.commit_description)
+                    .placeholder("Commit Description")
+                    .height(Length::Fixed(72f32))
+                    .on_action(Interaction::CommitDescriptionAction)
+                    .key_binding(editor_binding)
             )
             .push(
-                Button::new(Text::new("Back to Workspaces List"))
-                    .style(small_button)
-                    .on_press(Interaction::DisplayWorkspacesList)
+                Row::new()
+                    .push(PickList::new(self.collaborators.clone(), None::<CollaboratorInfo>, Interaction::AddCoAuthor).placeholder("Add Co-Author"))
+                    .extend(self.co_authors.iter().map(|collaborator| {
+                        Button::new(Text::new(format!("{} \u{00d7}", collaborator.login)).size(12))
+                            .style(small_button)
+                            .on_press(Interaction::RemoveCoAuthor(collaborator.login.clone()))
+                            .into()
+                    }))
+                    .spacing(5)
+                    .align_y(Vertical::Center)
             )
-            .padding(25)
-            .spacing(25)
-            .width(Length::Fill)
-            .align_x(Horizontal::Center)
+            .push({
+                let author = match self.commit_author() {
+                    Some(author) => format!("{} <{}>", author.name, author.email),
+                    None => "Your GitHub Account".to_string()
+                };
+                Text::new(format!("Author: {}", author)).size(10)
+            })
+            .spacing(10)
             .into()
     }
 
-    fn display_content<'a>(&self, structure: &'a BTreeMap<String, PathInfo>, indentation: f32, vec: &mut Vec<Element<'a, Interaction>>) {
-        for (_, value) in structure {
-            let is_dir = matches!(&value.content, PathContent::Directory(_));
-            let modifier = Button::new(
-                Svg::new(svg::Handle::from_memory(if is_dir { APPEND } else { REPLACE })).width(Length::Fixed(16f32))
-                    .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
-            ).style(small_button).on_press(Interaction::SelectFiles(is_dir, value.path.clone()));
-            let rename = Button::new(
-                Svg::new(svg::Handle::from_memory(RENAME)).width(Length::Fixed(16f32))
-                    .style(|t, s| advanced_svg(Color::from_rgb8(0, 255, 0), t, s))
-            ).style(small_button).on_press(Interaction::RefactorFiles(value.path.clone()));
-            let remove = Button::new(
-                Svg::new(svg::Handle::from_memory(REMOVE)).width(Length::Fixed(16f32))
-                    .style(|t, s| advanced_svg(Color::from_rgb8(255, 0, 0), t, s))
-            ).style(small_button).on_press(Interaction::EraseFiles(value.path.clone()));
-            let operations = Container::new(
-                Row::new()
-                    .push(modifier).push(rename).push(remove)
-                    .spacing(2.5)
-                    .align_y(Vertical::Center)
-            ).padding(Padding::new(0.0).right(2.5)).center_y(Length::Fill).align_right(Length::Fill);
-            match &value.content {
-                PathContent::File(info) => {
-                    let file = if !self.refactors.contains_key(&value.path) {
-                        widget::hover(
-                            Button::new(Text::new(&info.name))
-                                .padding(Padding::new(3.0).left(9.0))
-                                .width(Length::Fill)
-                                .style(small_button)
-                                .on_press(Interaction::ViewFile((&value.sha).to_string(), (&value).path.to_string())),
-                            operations
-                        )
-                    } else {
-                        TextInput::new("Rename File", self.refactors.get(&value.path).unwrap())
-                            .on_input(|input| Interaction::RefactorInput(value.path.to_string(), input))
-                            .on_submit(Interaction::PushRefactor(value.path.to_string(), value.sha.to_string()))
-                            .into()
-                    };
-                    if value.contains(&self.workspace_content_filter) {
-                        vec.push(Container::new(file).width(Length::Fixed(350f32)).padding(Padding::new(2.5).left(2.5 + indentation)).into());
-                    }
-                }
-                PathContent::Directory(info) => {
-                    let cds = self.collapsed_directories.clone();
-                    let directory = if !self.refactors.contains_key(&value.path) {
-                        let management_button = if cds.contains(&value.path) {
-                            Button::new(Svg::new(svg::Handle::from_memory(EXPAND)).width(Length::Fixed(16f32)).style(default_svg))
-                                .padding(3)
-                                .style(small_button)
-                                .on_press(Interaction::ExpandDirectory(value.path.to_string()))
-                        } else {
-                            Button::new(Svg::new(svg::Handle::from_memory(COLLAPSE)).width(Length::Fixed(16f32)).style(default_svg))
-                                .padding(3)
-                                .style(small_button)
-                                .on_press(Interaction::CollapseDirectory(value.path.to_string()))
-                        };
-                        widget::hover(
-                            Button::new(
-                                Row::new()
-                                    .push(management_button)
-                                    .push(Svg::new(svg::Handle::from_memory(FOLDER)).width(Length::Fixed(16f32)).style(default_svg))
-                                    .push(Text::new(&info.name))
-                                    .spacing(5)
-                                    .align_y(Vertical::Center)
-                            ).width(Length::Fill).padding(3).style(small_button),
-                            operations
-                        )
-                    } else {
-                        TextInput::new("Rename Directory", self.refactors.get(&value.path).unwrap())
-                            .on_input(|input| Interaction::RefactorInput(value.path.to_string(), input))
-                            .on_submit(Interaction::PushRefactor(value.path.to_string(), value.sha.to_string()))
-                            .into()
-                    };
-                    if value.contains(&self.workspace_content_filter) {
-                        vec.push(Container::new(directory).width(Length::Fixed(350f32)).padding(Padding::new(2.5).left(2.5 + indentation)).into());
-                        if !cds.contains(&value.path) {
-                            let mut inner = vec![];
-                            self.display_content(&info.contents, indentation + 15.0, &mut inner);
-                            vec.push(Column::new().extend(inner).into());
-                        }
-                    }
-                }
-            }
+    fn history_button<'a>(&self, icon: &'static [u8], label: Option<&'a String>, action: &'a str, interaction: Interaction) -> Element<'a, Interaction, Theme, Renderer> {
+        let button = Button::new(Svg::new(svg::Handle::from_memory(icon)).width(Length::Fixed(16f32)).style(default_svg))
+            .padding(5)
+            .style(small_button)
+            .on_press_maybe(label.map(|_| interaction));
+        match label {
+            Some(label) => Tooltip::new(button, Text::new(format!("{} {}", action, label)).size(12), tooltip::Position::Top).into(),
+            None => button.into()
         }
     }
 
     fn workspace_content(&self) -> Element<'_, Interaction, Theme, Renderer> {
         match &self.workspace_content {
-            Some(workspace_content) => {
+            Some(_) => {
+                // Listed once per frame, as both the pending changes and the bottom bar count them.
+                let pending = self.modification.pending();
+                let staged = self.staged_paths(&pending).len();
                 let append = Button::new(
                     Svg::new(svg::Handle::from_memory(APPEND)).width(Length::Fixed(16f32))
                         .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
                 ).style(small_button).on_press(Interaction::SelectFiles(true, "".to_string()));
+                let import = Button::new(
+                    Svg::new(svg::Handle::from_memory(IMPORT)).width(Length::Fixed(16f32))
+                        .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+                ).style(small_button).on_press(Interaction::SelectFolder("".to_string()));
+                let new_folder = Button::new(
+                    Svg::new(svg::Handle::from_memory(NEW_FOLDER)).width(Length::Fixed(16f32))
+                        .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+                ).style(small_button).on_press(Interaction::CreateEntry("".to_string(), true));
+                let new_file = Button::new(
+                    Svg::new(svg::Handle::from_memory(NEW_FILE)).width(Length::Fixed(16f32))
+                        .style(|t, s| advanced_svg(Color::from_rgb8(0, 125, 125), t, s))
+                ).style(small_button).on_press(Interaction::CreateEntry("".to_string(), false));
                 let workspace = self.selected_workspace.clone().unwrap();
-                let repository = widget::hover(
-                    Button::new(
-                        Row::new()
-                            .push(Svg::new(svg::Handle::from_memory(REPOSITORY)).width(Length::Fixed(16f32)).style(default_svg))
-                            .push(Text::new(format!("{}/{}", workspace.project.source_owner, workspace.project.source_name)))
-                            .push(Text::new(workspace.workspace_full_id).size(9))
-                            .spacing(5)
-                            .align_y(Vertical::Center)
-                    ).width(Length::Fill).padding(6).style(small_button),
-                    Container::new(append).padding(Padding::new(0.0).right(2.5)).center_y(Length::Fill).align_right(Length::Fill)
-                );
-                let mut root = vec![];
-                self.display_content(workspace_content, 15.0, &mut root);
+                let repository: Element<Interaction> = if self.creation.as_ref().is_some_and(|creation| creation.directory.is_empty()) {
+                    self.creation_input()
+                } else {
+                    widget::hover(
+                        Button::new(
+                            Row::new()
+                                .push(Svg::new(svg::Handle::from_memory(REPOSITORY)).width(Length::Fixed(16f32)).style(default_svg))
+                                .push(Text::new(format!("{}/{}", workspace.project.source_owner, workspace.project.source_name)))
+                                .push(Text::new(workspace.workspace_full_id).size(9))
+                                .spacing(5)
+                                .align_y(Vertical::Center)
+                        ).width(Length::Fill).padding(6).style(small_button),
+                        Container::new(Row::new().push(append).push(import).push(new_folder).push(new_file).spacing(2.5)).padding(Padding::new(0.0).right(2.5)).center_y(Length::Fill).align_right(Length::Fill)
+                    )
+                };
+                let highlighted = (self.files_hovered || self.dragged.is_some()) && self.drop_target.is_empty();
+                let repository = mouse_area(
+                    Container::new(repository)
+                        .width(Length::Fixed(350f32))
+                        .height(Length::Fixed(TREE_HEADER_HEIGHT))
+                        .padding(2.5)
+                        .style(move |theme| tree_row(theme, highlighted, false))
+                ).on_enter(Interaction::HoverDropTarget("".to_string()));
                 let scroll = Scrollable::new(
                     Column::new()
-                        .push(Container::new(repository).width(Length::Fixed(350f32)).padding(2.5))
-                        .extend(root).padding(10)).width(Length::Fixed(374f32)
-                );
+                        .push(repository)
+                        .extend(self.display_content()).padding(TREE_PADDING)
+                ).id(scrollable::Id::new("workspace-tree")).on_scroll(Interaction::ScrollWorkspaceContent).width(Length::Fixed(374f32));
+                let scroll = mouse_area(scroll).on_enter(Interaction::HoverTree(true)).on_exit(Interaction::HoverTree(false));
                 let mut content: Vec<Element<Interaction, Theme, Renderer>> = vec![];
                 let o_view = self.viewed_file_content.clone();
-                if matches!(o_view, Some(_)) {
+                if self.import_preview.is_some() {
+                    content.push(
+                        Container::new(self.import_preview())
+                            .width(Length::Fill)
+                            .height(Length::FillPortion(9))
+                            .padding(Padding::new(15f32).bottom(5))
+                            .into()
+                    );
+                }
+                else if !self.selection.is_empty() {
+                    content.push(
+                        Container::new(self.selection_panel())
+                            .width(Length::Fill)
+                            .height(Length::FillPortion(9))
+                            .padding(Padding::new(15f32).bottom(5))
+                            .into()
+                    );
+                }
+                else if self.is_loading(&Loading::Diff) {
+                    content.push(Container::new(self.loading_icon(32f32)).center(Length::FillPortion(9)).into());
+                }
+                else if let Some(image_diff) = &self.image_diff {
+                    content.push(
+                        Container::new(self.image_diff(image_diff))
+                            .width(Length::Fill)
+                            .height(Length::FillPortion(9))
+                            .padding(Padding::new(15f32).bottom(5))
+                            .into()
+                    );
+                }
+                else if let Some(diff) = &self.diff {
+                    content.push(
+                        Container::new(self.file_diff(diff))
+                            .width(Length::Fill)
+                            .height(Length::FillPortion(9))
+                            .padding(Padding::new(15f32).bottom(5))
+                            .into()
+                    );
+                }
+                else if self.show_pending_changes {
+                    content.push(
+                        Container::new(self.pending_changes(&pending, staged))
+                            .width(Length::Fill)
+                            .height(Length::FillPortion(9))
+                            .padding(Padding::new(15f32).bottom(5))
+                            .into()
+                    );
+                }
+                else if self.is_loading(&Loading::File) {
+                    content.push(Container::new(self.loading_icon(32f32)).center(Length::FillPortion(9)).into());
+                }
+                else if let Some(editor) = &self.file_editor {
+                    content.push(
+                        Container::new(self.file_editor(editor))
+                            .width(Length::Fill)
+                            .height(Length::FillPortion(9))
+                            .padding(Padding::new(15f32).bottom(5))
+                            .into()
+                    );
+                }
+                else if matches!(o_view, Some(_)) {
                     let name = self.viewed_file_name.clone().unwrap();
-                    if name.ends_with("png") || name.ends_with("jpg") || name.ends_with("jpeg") {
+                    if let Some(metadata) = &self.viewed_font {
                         content.push(
-                            Container::new(
-                                Viewer::new(image::Handle::from_bytes(o_view.unwrap()))
-                                    .width(Length::Fill)
-                                    .height(Length::Fill)
-                                    .filter_method(FilterMethod::Nearest)
-                                    .scale_step(0.5)
-                            ).center(Length::FillPortion(9)).padding(Padding::new(15f32).bottom(5)).into()
+                            Container::new(self.font_preview(metadata))
+                                .width(Length::Fill)
+                                .height(Length::FillPortion(9))
+                                .padding(Padding::new(15f32).bottom(5))
+                                .into()
+                        )
+                    }
+                    else if let Some(clip) = &self.viewed_audio {
+                        content.push(
+                            Container::new(self.audio_preview(clip))
+                                .center(Length::FillPortion(9))
+                                .padding(Padding::new(15f32).bottom(5))
+                                .into()
+                        )
+                    }
+                    else if let Some(preview) = &self.viewed_image {
+                        content.push(
+                            Container::new(self.image_preview(preview))
+                                .center(Length::FillPortion(9))
+                                .padding(Padding::new(15f32).bottom(5))
+                                .into()
                         )
                     }
                     else if name.ends_with("md") {
                         let value = String::from_utf8(o_view.unwrap()).unwrap();
                         let items = markdown::parse(&value).map(|x| &*Box::leak(Box::new(x)));
                         let display: Element<Interaction, Theme, Renderer> = widget::markdown(items, markdown::Settings::default(), markdown::Style::from_palette(Palette::DARK)).map(Interaction::OpenLink);
+                        let header = Row::new()
+                            .push(Space::with_width(Length::Fill))
+                            .push(Button::new("Edit").style(small_button).on_press(Interaction::EditFile));
+                        content.push(
+                            Container::new(Column::new().push(header).push(Scrollable::new(display).spacing(10)).spacing(10))
+                                .width(Length::Fill)
+                                .height(Length::FillPortion(9))
+                                .padding(Padding::new(15f32).bottom(5))
+                                .into()
+                        );
+                    }
+                    else if let Some(preview) = &self.viewed_text {
+                        content.push(
+                            Container::new(self.text_preview(preview))
+                                .width(Length::Fill)
+                                .height(Length::FillPortion(9))
+                                .padding(Padding::new(15f32).bottom(5))
+                                .into()
+                        );
+                    }
+                    else {
+                        let bytes = o_view.unwrap();
+                        let binary = Column::new()
+                            .push(Text::new(format!("Binary file, {} bytes", bytes.len())).size(20))
+                            .push(
+                                Scrollable::new(Text::new(viewer::hex_preview(&bytes)).font(Font::MONOSPACE).size(12))
+                                    .direction(scrollable::Direction::Both { vertical: scrollable::Scrollbar::default(), horizontal: scrollable::Scrollbar::default() })
+                                    .width(Length::Fill)
+                                    .height(Length::Fill)
+                            )
+                            .spacing(10);
                         content.push(
-                            Container::new(Scrollable::new(display).spacing(10))
+                            Container::new(binary)
                                 .width(Length::Fill)
                                 .height(Length::FillPortion(9))
                                 .padding(Padding::new(15f32).bottom(5))
@@ -927,17 +3417,39 @@ impl IllusionnaApp {
                         );
                     }
                 }
-                let bottom_bar = if self.modification.present() {
+                let history = Row::new()
+                    .push(self.history_button(UNDO, self.history.next_undo(), "Undo", Interaction::Undo))
+                    .push(self.history_button(REDO, self.history.next_redo(), "Redo", Interaction::Redo))
+                    .spacing(2.5);
+                let bottom_bar = if let Some(progress) = self.loading.get(&Loading::Sending) {
+                    Container::new(
+                        Row::new()
+                            .push(ProgressBar::new(0.0..=1.0, self.displayed_progress).height(Length::Fixed(10f32)))
+                            .push(Text::new(format!("Sending Changes ({}%)", (progress.unwrap_or(0.0) * 100.0).round())))
+                            .spacing(10)
+                            .align_y(Vertical::Center)
+                    )
+                } else if self.modification.present() {
                     Container::new(
                         Row::new()
-                            .push(TextInput::new("Modification Name", &self.modification_name).on_input(Interaction::ModificationNameInput))
-                            .push(Button::new("Send Changes").style(small_button).on_press(Interaction::SendChanges))
+                            .push(history)
+                            .push(TextInput::new("Commit Summary", &self.modification_name).on_input(Interaction::ModificationNameInput))
+                            .push(Button::new(Text::new(format!("Pending ({})", pending.len()))).style(small_button).on_press(Interaction::TogglePendingChanges))
+                            .push(
+                                Button::new(Text::new(match staged {
+                                    0 => "Send Changes".to_string(),
+                                    staged => format!("Send Staged ({})", staged)
+                                })).style(small_button).on_press(Interaction::SendChanges)
+                            )
                             .push(Button::new("Cancel Changes").style(small_button).on_press(Interaction::ResetChanges))
                             .spacing(10)
                             .align_y(Vertical::Center)
                     )
                 } else {
                     let mut unmodified: Vec<Element<Interaction, Theme, Renderer>> = vec![];
+                    if self.history.can_undo() || self.history.can_redo() {
+                        unmodified.push(history.into());
+                    }
                     unmodified.push(
                         Button::new("Return back to Workspaces List")
                             .style(small_button)
@@ -946,6 +3458,17 @@ impl IllusionnaApp {
                     );
                     Container::new(Row::new().extend(unmodified).spacing(10).align_y(Vertical::Center)).align_right(Length::Fill)
                 };
+                if let Some(notice) = &self.notice {
+                    content.push(
+                        Container::new(
+                            Row::new()
+                                .push(Text::new(notice).size(12).color(Color::from_rgb8(230, 170, 40)).width(Length::Fill))
+                                .push(Button::new(Text::new("Dismiss").size(12)).style(small_button).on_press(Interaction::DismissNotice))
+                                .spacing(10)
+                                .align_y(Vertical::Center)
+                        ).padding(Padding::new(10f32).top(0f32)).into()
+                    );
+                }
                 content.push(
                     Container::new(bottom_bar)
                         .align_bottom(Length::FillPortion(1))
@@ -956,9 +3479,17 @@ impl IllusionnaApp {
                     .push(
                         Column::new()
                             .push(
-                                TextInput::new("Filter Elements", &self.workspace_content_filter)
+                                Row::new()
+                                    .push(
+                                        TextInput::new("Filter Elements", &self.workspace_content_filter)
+                                            .on_input(Interaction::FilterWorkspaceContent)
+                                    )
+                                    .push_maybe((!self.workspace_content_filter.is_empty()).then(|| {
+                                        Button::new(Text::new("Select").size(12)).style(small_button).on_press(Interaction::SelectMatches)
+                                    }))
+                                    .spacing(3)
                                     .width(Length::Fixed(374f32))
-                                    .on_input(Interaction::FilterWorkspaceContent)
+                                    .align_y(Vertical::Center)
                             )
                             .push(scroll)
                             .spacing(3)
@@ -0,0 +1,379 @@
+use ::image::{Rgba, RgbaImage};
+use iced::widget::image;
+use rodio::source::SeekError;
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use std::io::{Cursor, ErrorKind};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Size of the waveform image, which the viewer stretches to its width.
+const WAVEFORM_WIDTH: u32 = 640;
+const WAVEFORM_HEIGHT: u32 = 96;
+
+/// Audio formats recognized by their content rather than by the extension of their name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Ogg,
+    Wav
+}
+
+impl AudioFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Wav => "wav"
+        }
+    }
+}
+
+/// Recognizes sounds from their magic bytes, as resource packs only ship Ogg Vorbis files but
+/// their sources are often kept as WAV files.
+pub fn sniff_audio(bytes: &[u8]) -> Option<AudioFormat> {
+    if bytes.starts_with(b"OggS") {
+        Some(AudioFormat::Ogg)
+    }
+    else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WAVE" {
+        Some(AudioFormat::Wav)
+    }
+    else {
+        None
+    }
+}
+
+/// A sound decoded into interleaved samples, shared between the viewer and the player.
+#[derive(Debug, Clone)]
+pub struct AudioClip {
+    pub codec: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration: Duration,
+    pub samples: Arc<Vec<f32>>,
+    pub waveform: image::Handle
+}
+
+impl AudioClip {
+    pub fn channel_name(&self) -> String {
+        match self.channels {
+            1 => "Mono".to_string(),
+            2 => "Stereo".to_string(),
+            channels => format!("{} Channels", channels)
+        }
+    }
+}
+
+/// Decodes a whole sound into samples, returning None when it is not a sound. It is decoded on
+/// a blocking thread, as long music files take a while.
+pub async fn decode_audio(bytes: Vec<u8>) -> Option<Result<AudioClip, String>> {
+    let format = sniff_audio(&bytes)?;
+    Some(tokio::task::spawn_blocking(move || decode(format, bytes)).await.unwrap_or_else(|error| Err(error.to_string())))
+}
+
+fn decode(format: AudioFormat, bytes: Vec<u8>) -> Result<AudioClip, String> {
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(format.extension());
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|error| error.to_string())?;
+    let mut reader = probed.format;
+    let track = reader.tracks().iter().find(|track| track.codec_params.codec != CODEC_TYPE_NULL).ok_or("No audio track was found.")?;
+    let track_id = track.id;
+    let codec = symphonia::default::get_codecs()
+        .get_codec(track.codec_params.codec)
+        .map(|descriptor| descriptor.long_name.to_string())
+        .unwrap_or("Unknown Codec".to_string());
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or_default();
+    let mut channels = track.codec_params.channels.map(|channels| channels.count()).unwrap_or_default();
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|error| error.to_string())?;
+    let mut samples = vec![];
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof => break,
+            Err(Error::ResetRequired) => break,
+            Err(error) => return Err(error.to_string())
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+                sample_rate = spec.rate;
+                channels = spec.channels.count();
+            }
+            // A corrupted packet only loses a few milliseconds, the rest is still worth hearing.
+            Err(Error::DecodeError(_)) => continue,
+            Err(error) => return Err(error.to_string())
+        }
+    }
+    if sample_rate == 0 || channels == 0 {
+        return Err("The sample rate or the channels of the sound are unknown.".to_string());
+    }
+    let frames = samples.len() / channels;
+    Ok(AudioClip {
+        codec,
+        sample_rate,
+        channels: channels as u16,
+        duration: Duration::from_secs_f64(frames as f64 / sample_rate as f64),
+        waveform: waveform(&samples, channels),
+        samples: Arc::new(samples)
+    })
+}
+
+/// Draws the peaks of the sound, all channels mixed, each column spanning from the lowest to the
+/// highest sample of its slice of the sound.
+fn waveform(samples: &[f32], channels: usize) -> image::Handle {
+    let mut pixels = RgbaImage::new(WAVEFORM_WIDTH, WAVEFORM_HEIGHT);
+    let frames = samples.len() / channels;
+    let middle = WAVEFORM_HEIGHT as f32 / 2.0;
+    for x in 0..WAVEFORM_WIDTH {
+        let start = frames * x as usize / WAVEFORM_WIDTH as usize;
+        let end = (frames * (x as usize + 1) / WAVEFORM_WIDTH as usize).max(start + 1).min(frames);
+        let (mut low, mut high) = (0f32, 0f32);
+        for frame in start..end {
+            let sample = samples[frame * channels..(frame + 1) * channels].iter().sum::<f32>() / channels as f32;
+            low = low.min(sample);
+            high = high.max(sample);
+        }
+        let top = (middle - high.clamp(-1.0, 1.0) * middle).floor() as u32;
+        let bottom = (middle - low.clamp(-1.0, 1.0) * middle).ceil() as u32;
+        for y in top.min(WAVEFORM_HEIGHT - 1)..=bottom.min(WAVEFORM_HEIGHT - 1) {
+            pixels.put_pixel(x, y, Rgba([90, 150, 230, 255]));
+        }
+    }
+    image::Handle::from_rgba(WAVEFORM_WIDTH, WAVEFORM_HEIGHT, pixels.into_raw())
+}
+
+/// Plays the samples of a clip from where they are shared, so that playing or seeking the sound
+/// again does not copy them.
+struct ClipSource {
+    samples: Arc<Vec<f32>>,
+    position: usize,
+    channels: u16,
+    sample_rate: u32,
+    duration: Duration
+}
+
+impl ClipSource {
+    fn new(clip: &AudioClip) -> ClipSource {
+        ClipSource {
+            samples: clip.samples.clone(),
+            position: 0,
+            channels: clip.channels,
+            sample_rate: clip.sample_rate,
+            duration: clip.duration
+        }
+    }
+}
+
+impl Iterator for ClipSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied()?;
+        self.position += 1;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.samples.len().saturating_sub(self.position);
+        (remaining, Some(remaining))
+    }
+}
+
+impl Source for ClipSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+
+    /// Moves to the start of the frame at the position, staying on the channel that comes next so
+    /// that the output does not swap them.
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        let channels = self.channels as usize;
+        let frames = self.samples.len() / channels;
+        let frame = ((position.as_secs_f64() * self.sample_rate as f64) as usize).min(frames);
+        self.position = frame * channels + self.position % channels;
+        Ok(())
+    }
+}
+
+/// Plays the viewed sound on the default output device, which is only opened once something is
+/// played, so that machines without any audio device can still browse sounds.
+pub struct AudioPlayer {
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    sink: Sink
+}
+
+impl std::fmt::Debug for AudioPlayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioPlayer").field("paused", &self.sink.is_paused()).field("position", &self.sink.get_pos()).finish()
+    }
+}
+
+impl AudioPlayer {
+    pub fn new() -> Result<AudioPlayer, String> {
+        let (stream, handle) = OutputStream::try_default().map_err(|error| error.to_string())?;
+        let sink = Sink::try_new(&handle).map_err(|error| error.to_string())?;
+        sink.pause();
+        Ok(AudioPlayer { _stream: stream, handle, sink })
+    }
+
+    pub fn is_playing(&self) -> bool {
+        !self.sink.is_paused() && !self.sink.empty()
+    }
+
+    /// Position in the sound, which is back at the start once it has been played to its end.
+    pub fn position(&self) -> Duration {
+        if self.sink.empty() { Duration::ZERO } else { self.sink.get_pos() }
+    }
+
+    /// Plays the sound, queuing it again when it was played to its end.
+    pub fn play(&self, clip: &AudioClip) {
+        self.queue(clip);
+        self.sink.play();
+    }
+
+    pub fn pause(&self) {
+        self.sink.pause();
+    }
+
+    /// Moves to a position in the sound, without playing it when it was paused.
+    pub fn seek(&self, clip: &AudioClip, position: Duration) {
+        if self.sink.empty() {
+            self.sink.pause();
+        }
+        self.queue(clip);
+        let _ = self.sink.try_seek(position);
+    }
+
+    fn queue(&self, clip: &AudioClip) {
+        if self.sink.empty() {
+            self.sink.append(ClipSource::new(clip));
+        }
+    }
+
+    /// Stops the sound when another file is viewed. The sink is replaced rather than cleared, as
+    /// clearing it waits for the sound to end, which never happens while it is paused.
+    pub fn stop(&mut self) {
+        if let Ok(sink) = Sink::try_new(&self.handle) {
+            sink.pause();
+            self.sink = sink;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a 16-bit PCM WAV file holding a sine wave on every channel.
+    fn wav(sample_rate: u32, channels: u16, frames: u32) -> Vec<u8> {
+        let data = frames * channels as u32 * 2;
+        let mut bytes = vec![];
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + data).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(channels.to_le_bytes());
+        bytes.extend(sample_rate.to_le_bytes());
+        bytes.extend((sample_rate * channels as u32 * 2).to_le_bytes());
+        bytes.extend((channels * 2).to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(data.to_le_bytes());
+        for frame in 0..frames {
+            let sample = ((frame as f32 * 0.05).sin() * 16000.0) as i16;
+            for _ in 0..channels {
+                bytes.extend(sample.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn sounds_are_sniffed_from_their_magic_bytes() {
+        assert_eq!(sniff_audio(b"OggS\x00\x02"), Some(AudioFormat::Ogg));
+        assert_eq!(sniff_audio(&wav(8000, 1, 1)), Some(AudioFormat::Wav));
+        assert_eq!(sniff_audio(b"RIFF\x00\x00\x00\x00WEBPVP8 "), None);
+        assert_eq!(sniff_audio(b"RIFF"), None);
+        assert_eq!(sniff_audio(b"\x89PNG\r\n\x1a\n"), None);
+        assert_eq!(sniff_audio(b""), None);
+    }
+
+    #[test]
+    fn wav_files_are_decoded_to_interleaved_samples() {
+        let clip = decode(AudioFormat::Wav, wav(44100, 2, 22050)).unwrap();
+        assert_eq!(clip.sample_rate, 44100);
+        assert_eq!(clip.channels, 2);
+        assert_eq!(clip.channel_name(), "Stereo");
+        assert_eq!(clip.duration, Duration::from_millis(500));
+        assert_eq!(clip.samples.len(), 44100);
+        assert!(clip.samples.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+        assert!(clip.samples.iter().any(|sample| *sample > 0.4));
+    }
+
+    #[test]
+    fn mono_wav_files_keep_their_length() {
+        let clip = decode(AudioFormat::Wav, wav(8000, 1, 8000)).unwrap();
+        assert_eq!((clip.sample_rate, clip.channels), (8000, 1));
+        assert_eq!(clip.duration, Duration::from_secs(1));
+        assert_eq!(clip.samples.len(), 8000);
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        assert!(decode(AudioFormat::Wav, wav(44100, 2, 100)[..20].to_vec()).is_err());
+        assert!(decode(AudioFormat::Ogg, b"OggS".to_vec()).is_err());
+    }
+
+    #[test]
+    fn clip_sources_share_the_samples_of_the_clip() {
+        let clip = decode(AudioFormat::Wav, wav(100, 2, 100)).unwrap();
+        let mut source = ClipSource::new(&clip);
+        assert!(Arc::ptr_eq(&source.samples, &clip.samples));
+        assert_eq!((source.channels(), source.sample_rate()), (2, 100));
+        assert_eq!(source.total_duration(), Some(Duration::from_secs(1)));
+        assert_eq!(source.by_ref().collect::<Vec<f32>>(), *clip.samples);
+        assert_eq!(source.next(), None);
+    }
+
+    #[test]
+    fn clip_sources_seek_to_frames_without_swapping_channels() {
+        let samples = (0..400).map(|sample| sample as f32).collect::<Vec<f32>>();
+        let clip = AudioClip { samples: Arc::new(samples), ..decode(AudioFormat::Wav, wav(100, 2, 200)).unwrap() };
+        let mut source = ClipSource::new(&clip);
+        source.try_seek(Duration::from_millis(500)).unwrap();
+        assert_eq!(source.next(), Some(100.0));
+        source.try_seek(Duration::from_secs(1)).unwrap();
+        assert_eq!(source.next(), Some(201.0));
+        source.try_seek(Duration::from_secs(10)).unwrap();
+        assert_eq!(source.next(), None);
+    }
+}
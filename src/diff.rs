@@ -0,0 +1,548 @@
+use crate::viewer;
+use ::image::{Rgba, RgbaImage};
+use iced::widget::image;
+
+/// Unchanged lines kept around each change when the diff is split into hunks.
+const CONTEXT_LINES: usize = 3;
+
+/// Edits searched at most by the Myers algorithm, as the trace kept to backtrack grows with their
+/// square. Sequences further apart are diffed as the removal of one and the addition of the other.
+const MAX_EDITS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTag {
+    Equal,
+    Removed,
+    Added
+}
+
+/// A line of a diff, split into segments flagged when they changed within the line.
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    pub old_number: Option<usize>,
+    pub new_number: Option<usize>,
+    pub segments: Vec<(String, bool)>
+}
+
+/// Consecutive lines of a diff containing changes and the unchanged lines around them.
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    pub header: String,
+    pub lines: Vec<DiffLine>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize)
+}
+
+/// Finds the edit script between two sequences. Their common prefix and suffix are set aside
+/// first, so that only the changed middle goes through the Myers algorithm, when neither side of
+/// it is empty.
+fn myers<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    let (old_middle, new_middle) = (&old[prefix..old_end], &new[prefix..new_end]);
+    let mut edits = (0..prefix).map(|i| Edit::Equal(i, i)).collect::<Vec<Edit>>();
+    let middle = match old_middle.is_empty() || new_middle.is_empty() {
+        true => None,
+        false => shortest_edits(old_middle, new_middle)
+    };
+    match middle {
+        Some(middle) => edits.extend(middle.into_iter().map(|edit| match edit {
+            Edit::Equal(i, j) => Edit::Equal(i + prefix, j + prefix),
+            Edit::Delete(i) => Edit::Delete(i + prefix),
+            Edit::Insert(j) => Edit::Insert(j + prefix)
+        })),
+        None => {
+            edits.extend((prefix..old_end).map(Edit::Delete));
+            edits.extend((prefix..new_end).map(Edit::Insert));
+        }
+    }
+    edits.extend((0..suffix).map(|i| Edit::Equal(old_end + i, new_end + i)));
+    edits
+}
+
+/// Finds the shortest edit script between two sequences with the Myers algorithm, keeping the
+/// furthest reaching path of the diagonals reachable with each number of edits to backtrack.
+/// Returns None when the sequences are more than MAX_EDITS apart.
+fn shortest_edits<T: PartialEq>(old: &[T], new: &[T]) -> Option<Vec<Edit>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    let mut trace = vec![];
+    let mut found = false;
+    'search: for d in 0..=max.min(MAX_EDITS) as isize {
+        trace.push(v[(offset - d - 1) as usize..=(offset + d + 1) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) { v[index + 1] } else { v[index - 1] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+    let mut edits = vec![];
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let index = (k + d + 1) as usize;
+        let previous_k = if k == -d || (k != d && v[index - 1] < v[index + 1]) { k + 1 } else { k - 1 };
+        let previous_x = if d == 0 { 0 } else { v[(previous_k + d + 1) as usize] };
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == previous_x {
+                edits.push(Edit::Insert(previous_y as usize));
+            } else {
+                edits.push(Edit::Delete(previous_x as usize));
+            }
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+    edits.reverse();
+    Some(edits)
+}
+
+/// Splits a line into words, runs of whitespace and single punctuation characters.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut previous: Option<u8> = None;
+    for (i, c) in line.char_indices() {
+        let class = if c.is_alphanumeric() || c == '_' { 0 } else if c.is_whitespace() { 1 } else { 2 };
+        if i > 0 && (previous != Some(class) || class == 2) {
+            tokens.push(&line[start..i]);
+            start = i;
+        }
+        previous = Some(class);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// Compares a removed line with the added line replacing it, flagging the words that differ.
+fn diff_words(old: &str, new: &str) -> (Vec<(String, bool)>, Vec<(String, bool)>) {
+    let (old_tokens, new_tokens) = (tokenize(old), tokenize(new));
+    let mut removed: Vec<(String, bool)> = vec![];
+    let mut added: Vec<(String, bool)> = vec![];
+    let push = |segments: &mut Vec<(String, bool)>, token: &str, changed: bool| match segments.last_mut() {
+        Some((text, flag)) if *flag == changed => text.push_str(token),
+        _ => segments.push((token.to_string(), changed))
+    };
+    for edit in myers(&old_tokens, &new_tokens) {
+        match edit {
+            Edit::Equal(i, j) => {
+                push(&mut removed, old_tokens[i], false);
+                push(&mut added, new_tokens[j], false);
+            }
+            Edit::Delete(i) => push(&mut removed, old_tokens[i], true),
+            Edit::Insert(j) => push(&mut added, new_tokens[j], true)
+        }
+    }
+    (removed, added)
+}
+
+/// Computes the line diff between two texts. Removed lines directly followed by added lines are
+/// paired in order and compared word by word.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let (old_lines, new_lines) = (old.lines().collect::<Vec<&str>>(), new.lines().collect::<Vec<&str>>());
+    let mut lines = vec![];
+    let mut removed = vec![];
+    let mut added = vec![];
+    let flush = |lines: &mut Vec<DiffLine>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        let mut pairs = removed.iter().zip(added.iter())
+            .map(|(i, j)| diff_words(old_lines[*i], new_lines[*j]))
+            .collect::<Vec<(Vec<(String, bool)>, Vec<(String, bool)>)>>()
+            .into_iter();
+        let mut added_segments = vec![];
+        for i in removed.drain(..) {
+            let segments = match pairs.next() {
+                Some((old_segments, new_segments)) => {
+                    added_segments.push(new_segments);
+                    old_segments
+                }
+                None => vec![(old_lines[i].to_string(), false)]
+            };
+            lines.push(DiffLine { tag: DiffTag::Removed, old_number: Some(i + 1), new_number: None, segments });
+        }
+        let mut added_segments = added_segments.into_iter();
+        for j in added.drain(..) {
+            let segments = added_segments.next().unwrap_or_else(|| vec![(new_lines[j].to_string(), false)]);
+            lines.push(DiffLine { tag: DiffTag::Added, old_number: None, new_number: Some(j + 1), segments });
+        }
+    };
+    for edit in myers(&old_lines, &new_lines) {
+        match edit {
+            Edit::Equal(i, j) => {
+                flush(&mut lines, &mut removed, &mut added);
+                lines.push(DiffLine { tag: DiffTag::Equal, old_number: Some(i + 1), new_number: Some(j + 1), segments: vec![(old_lines[i].to_string(), false)] });
+            }
+            Edit::Delete(i) => removed.push(i),
+            Edit::Insert(j) => added.push(j)
+        }
+    }
+    flush(&mut lines, &mut removed, &mut added);
+    lines
+}
+
+/// Groups the changed lines of a diff into hunks with a few unchanged lines of context.
+pub fn hunks(lines: &[DiffLine]) -> Vec<Hunk> {
+    let changed = lines.iter().enumerate()
+        .filter(|(_, line)| line.tag != DiffTag::Equal)
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    for i in changed {
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + CONTEXT_LINES + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end))
+        }
+    }
+    ranges.into_iter()
+        .map(|(start, end)| {
+            let lines = lines[start..end].to_vec();
+            let old_start = lines.iter().find_map(|line| line.old_number).unwrap_or(0);
+            let new_start = lines.iter().find_map(|line| line.new_number).unwrap_or(0);
+            let old_count = lines.iter().filter(|line| line.tag != DiffTag::Added).count();
+            let new_count = lines.iter().filter(|line| line.tag != DiffTag::Removed).count();
+            Hunk { header: format!("@@ -{},{} +{},{} @@", old_start, old_count, new_start, new_count), lines }
+        })
+        .collect()
+}
+
+/// Places the lines of a hunk side by side, removed lines facing the added lines that follow them.
+pub fn side_by_side(lines: &[DiffLine]) -> Vec<(Option<&DiffLine>, Option<&DiffLine>)> {
+    let mut rows = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].tag == DiffTag::Equal {
+            rows.push((Some(&lines[i]), Some(&lines[i])));
+            i += 1;
+            continue;
+        }
+        let removed = lines[i..].iter().take_while(|line| line.tag == DiffTag::Removed).collect::<Vec<&DiffLine>>();
+        let added = lines[i + removed.len()..].iter().take_while(|line| line.tag == DiffTag::Added).collect::<Vec<&DiffLine>>();
+        for row in 0..removed.len().max(added.len()) {
+            rows.push((removed.get(row).copied(), added.get(row).copied()));
+        }
+        i += removed.len() + added.len();
+    }
+    rows
+}
+
+/// The diff of a pending change, or the sizes of both sides when one of them is not text. Notes
+/// describe the changes that the lines do not show, like their endings.
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub hunks: Vec<Hunk>,
+    pub added: usize,
+    pub removed: usize,
+    pub binary: Option<(usize, usize)>,
+    pub notes: Vec<String>
+}
+
+impl FileDiff {
+    pub fn new(path: String, old: &[u8], new: &[u8]) -> FileDiff {
+        if !viewer::is_text(old) || !viewer::is_text(new) {
+            return FileDiff { path, hunks: vec![], added: 0, removed: 0, binary: Some((old.len(), new.len())), notes: vec![] };
+        }
+        let (old_text, new_text) = (String::from_utf8_lossy(old), String::from_utf8_lossy(new));
+        let lines = diff_lines(&old_text, &new_text);
+        let added = lines.iter().filter(|line| line.tag == DiffTag::Added).count();
+        let removed = lines.iter().filter(|line| line.tag == DiffTag::Removed).count();
+        let hunks = hunks(&lines);
+        let mut notes = ending_notes(&old_text, &new_text);
+        if notes.is_empty() && hunks.is_empty() && old != new {
+            notes.push("The contents differ only in characters the lines do not show.".to_string());
+        }
+        FileDiff { path, hunks, added, removed, binary: None, notes }
+    }
+}
+
+/// Names the line endings of a text, or None when it has a single line.
+fn line_endings(text: &str) -> Option<&'static str> {
+    match (text.matches('\n').count(), text.matches("\r\n").count()) {
+        (0, _) => None,
+        (newlines, crlf) if newlines == crlf => Some("CRLF"),
+        (_, 0) => Some("LF"),
+        _ => Some("mixed")
+    }
+}
+
+/// Describes the changes of the line endings and of the newline at the end of the file, which are
+/// dropped when the texts are split into lines. Added and deleted files have none.
+fn ending_notes(old: &str, new: &str) -> Vec<String> {
+    let mut notes = vec![];
+    if old.is_empty() || new.is_empty() {
+        return notes;
+    }
+    if let (Some(old_endings), Some(new_endings)) = (line_endings(old), line_endings(new)) {
+        if old_endings != new_endings {
+            notes.push(format!("Line endings changed from {} to {}.", old_endings, new_endings));
+        }
+    }
+    match (old.ends_with('\n'), new.ends_with('\n')) {
+        (true, false) => notes.push("Newline at end of file removed.".to_string()),
+        (false, true) => notes.push("Newline added at end of file.".to_string()),
+        _ => {}
+    }
+    notes
+}
+
+/// The diff of a pending change, which is an image diff when both sides are images.
+#[derive(Debug, Clone)]
+pub enum ChangeDiff {
+    Text(FileDiff),
+    Image(ImageDiff)
+}
+
+/// Diffs both sides of a change on a blocking thread, as large files take a while to compare.
+pub async fn diff_change(path: String, old: Vec<u8>, new: Vec<u8>) -> ChangeDiff {
+    tokio::task::spawn_blocking(move || match ImageDiff::new(path.clone(), &old, &new) {
+        Some(image_diff) => ChangeDiff::Image(image_diff),
+        None => ChangeDiff::Text(FileDiff::new(path, &old, &new))
+    }).await.unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageDiffMode {
+    SideBySide,
+    Swipe,
+    Onion,
+    Heatmap
+}
+
+impl ImageDiffMode {
+    pub const ALL: [ImageDiffMode; 4] = [ImageDiffMode::SideBySide, ImageDiffMode::Swipe, ImageDiffMode::Onion, ImageDiffMode::Heatmap];
+}
+
+impl std::fmt::Display for ImageDiffMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ImageDiffMode::SideBySide => "Side by Side",
+            ImageDiffMode::Swipe => "Swipe",
+            ImageDiffMode::Onion => "Onion Skin",
+            ImageDiffMode::Heatmap => "Heatmap"
+        })
+    }
+}
+
+/// The comparison of an image with the upstream image it replaces. Both images are compared
+/// pixel by pixel over the largest of their sizes, missing pixels being transparent.
+#[derive(Debug, Clone)]
+pub struct ImageDiff {
+    pub path: String,
+    pub old: image::Handle,
+    pub new: image::Handle,
+    pub old_size: (u32, u32),
+    pub new_size: (u32, u32),
+    pub heatmap: image::Handle,
+    pub changed: usize,
+    pub total: usize,
+    pub composite: image::Handle,
+    old_pixels: RgbaImage,
+    new_pixels: RgbaImage
+}
+
+impl ImageDiff {
+    /// Decodes both sides of a change, returning None unless both of them are images.
+    pub fn new(path: String, old: &[u8], new: &[u8]) -> Option<ImageDiff> {
+        let old_pixels = ::image::load_from_memory(old).ok()?.to_rgba8();
+        let new_pixels = ::image::load_from_memory(new).ok()?.to_rgba8();
+        let (width, height) = (old_pixels.width().max(new_pixels.width()), old_pixels.height().max(new_pixels.height()));
+        let mut changed = 0;
+        let heatmap = RgbaImage::from_fn(width, height, |x, y| {
+            let (before, after) = (pixel(&old_pixels, x, y), pixel(&new_pixels, x, y));
+            let difference = before.0.iter().zip(after.0.iter()).map(|(a, b)| a.abs_diff(*b)).max().unwrap();
+            if difference == 0 {
+                let luminance = (after[0] as u32 * 299 + after[1] as u32 * 587 + after[2] as u32 * 114) / 1000;
+                let dimmed = (luminance * after[3] as u32 / 255 / 4) as u8;
+                Rgba([dimmed, dimmed, dimmed, 255])
+            } else {
+                changed += 1;
+                Rgba([255, (255 - difference) / 2, 0, 255])
+            }
+        });
+        Some(ImageDiff {
+            path,
+            old: handle(&old_pixels),
+            new: handle(&new_pixels),
+            old_size: old_pixels.dimensions(),
+            new_size: new_pixels.dimensions(),
+            heatmap: handle(&heatmap),
+            changed,
+            total: (width * height) as usize,
+            composite: handle(&new_pixels),
+            old_pixels,
+            new_pixels
+        })
+    }
+
+    /// Recomposes the image shown by the swipe and onion skin modes, where the position is how much
+    /// of the new image the swipe uncovers from the left or how opaque it is over the old one. The
+    /// other modes show the images as they are, so there is nothing to compose for them.
+    pub fn compose(&mut self, mode: ImageDiffMode, position: f32) {
+        if !matches!(mode, ImageDiffMode::Swipe | ImageDiffMode::Onion) {
+            return;
+        }
+        let (width, height) = (self.old_pixels.width().max(self.new_pixels.width()), self.old_pixels.height().max(self.new_pixels.height()));
+        let split = (position * width as f32).round() as u32;
+        let composite = RgbaImage::from_fn(width, height, |x, y| {
+            let (before, after) = (pixel(&self.old_pixels, x, y), pixel(&self.new_pixels, x, y));
+            match mode {
+                ImageDiffMode::Swipe => if x >= split { before } else { after },
+                _ => Rgba(std::array::from_fn(|i| (before[i] as f32 + (after[i] as f32 - before[i] as f32) * position).round() as u8))
+            }
+        });
+        self.composite = handle(&composite);
+    }
+}
+
+fn pixel(pixels: &RgbaImage, x: u32, y: u32) -> Rgba<u8> {
+    match x < pixels.width() && y < pixels.height() {
+        true => *pixels.get_pixel(x, y),
+        false => Rgba([0, 0, 0, 0])
+    }
+}
+
+fn handle(pixels: &RgbaImage) -> image::Handle {
+    image::Handle::from_rgba(pixels.width(), pixels.height(), pixels.as_raw().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(lines: &[DiffLine]) -> Vec<DiffTag> {
+        lines.iter().map(|line| line.tag).collect()
+    }
+
+    /// Applies an edit script to the old sequence, which must give back the new one.
+    fn apply<T: PartialEq + Clone>(old: &[T], new: &[T], edits: &[Edit]) -> Vec<T> {
+        edits.iter().filter_map(|edit| match edit {
+            Edit::Equal(i, j) => {
+                assert!(old[*i] == new[*j]);
+                Some(old[*i].clone())
+            }
+            Edit::Delete(_) => None,
+            Edit::Insert(j) => Some(new[*j].clone())
+        }).collect()
+    }
+
+    #[test]
+    fn myers_finds_the_shortest_edit_script() {
+        let (old, new) = ("ABCABBA".chars().collect::<Vec<char>>(), "CBABAC".chars().collect::<Vec<char>>());
+        let edits = myers(&old, &new);
+        assert_eq!(apply(&old, &new, &edits), new);
+        assert_eq!(edits.iter().filter(|edit| !matches!(edit, Edit::Equal(..))).count(), 5);
+    }
+
+    #[test]
+    fn myers_gives_up_on_sequences_too_far_apart() {
+        let (old, new) = ((0..3000).collect::<Vec<u32>>(), (3000..6000).collect::<Vec<u32>>());
+        let edits = myers(&old, &new);
+        assert_eq!(apply(&old, &new, &edits), new);
+        assert!(edits[..3000].iter().all(|edit| matches!(edit, Edit::Delete(_))));
+        assert!(edits[3000..].iter().all(|edit| matches!(edit, Edit::Insert(_))));
+    }
+
+    #[test]
+    fn empty_sides_are_fully_added_or_removed() {
+        assert!(diff_lines("", "").is_empty());
+        let added = diff_lines("", "a\nb\n");
+        assert_eq!(tags(&added), vec![DiffTag::Added, DiffTag::Added]);
+        assert_eq!(added.iter().map(|line| line.new_number).collect::<Vec<Option<usize>>>(), vec![Some(1), Some(2)]);
+        let removed = diff_lines("a\nb\n", "");
+        assert_eq!(tags(&removed), vec![DiffTag::Removed, DiffTag::Removed]);
+        assert_eq!(removed.iter().map(|line| line.old_number).collect::<Vec<Option<usize>>>(), vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn pure_insertions_and_deletions_keep_the_other_lines() {
+        let inserted = diff_lines("a\nc\n", "a\nb\nc\n");
+        assert_eq!(tags(&inserted), vec![DiffTag::Equal, DiffTag::Added, DiffTag::Equal]);
+        assert_eq!(inserted[1].segments, vec![("b".to_string(), false)]);
+        assert_eq!((inserted[2].old_number, inserted[2].new_number), (Some(2), Some(3)));
+        let deleted = diff_lines("a\nb\nc\n", "a\nc\n");
+        assert_eq!(tags(&deleted), vec![DiffTag::Equal, DiffTag::Removed, DiffTag::Equal]);
+        assert_eq!(deleted[1].old_number, Some(2));
+    }
+
+    #[test]
+    fn replaced_lines_are_paired_word_by_word() {
+        let lines = diff_lines("let x = 1;\nend\n", "let y = 1;\nend\n");
+        assert_eq!(tags(&lines), vec![DiffTag::Removed, DiffTag::Added, DiffTag::Equal]);
+        assert_eq!(lines[0].segments, vec![("let ".to_string(), false), ("x".to_string(), true), (" = 1;".to_string(), false)]);
+        assert_eq!(lines[1].segments, vec![("let ".to_string(), false), ("y".to_string(), true), (" = 1;".to_string(), false)]);
+    }
+
+    #[test]
+    fn unpaired_lines_are_not_compared() {
+        let lines = diff_lines("a\n", "b\nc\n");
+        assert_eq!(tags(&lines), vec![DiffTag::Removed, DiffTag::Added, DiffTag::Added]);
+        assert_eq!(lines[2].segments, vec![("c".to_string(), false)]);
+    }
+
+    #[test]
+    fn hunks_keep_a_few_lines_of_context() {
+        let old = (1..=20).map(|i| i.to_string()).collect::<Vec<String>>().join("\n");
+        let new = old.replace("10", "ten");
+        let hunks = hunks(&diff_lines(&old, &new));
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].header, "@@ -7,7 +7,7 @@");
+        assert_eq!(hunks[0].lines.len(), 8);
+        assert_eq!(side_by_side(&hunks[0].lines).len(), 7);
+    }
+
+    #[test]
+    fn line_ending_conversions_are_noted() {
+        let diff = FileDiff::new("a.txt".to_string(), b"a\r\nb\r\n", b"a\nb\n");
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.notes, vec!["Line endings changed from CRLF to LF.".to_string()]);
+        let diff = FileDiff::new("a.txt".to_string(), b"a\nb\n", b"a\r\nb\n");
+        assert_eq!(diff.notes, vec!["Line endings changed from LF to mixed.".to_string()]);
+    }
+
+    #[test]
+    fn final_newline_changes_are_noted() {
+        let diff = FileDiff::new("a.txt".to_string(), b"a\nb\n", b"a\nb");
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.notes, vec!["Newline at end of file removed.".to_string()]);
+        let diff = FileDiff::new("a.txt".to_string(), b"a", b"b\n");
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.notes, vec!["Newline added at end of file.".to_string()]);
+    }
+
+    #[test]
+    fn identical_and_new_texts_have_no_notes() {
+        assert!(FileDiff::new("a.txt".to_string(), b"a\r\n", b"a\r\n").notes.is_empty());
+        assert!(FileDiff::new("a.txt".to_string(), b"", b"a\r\nb").notes.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::workspace::{Modification, ModificationDelta, PathInfo};
+use std::collections::BTreeMap;
+
+/// A reversible workspace edit. It keeps the top-level entries of the workspace tree it touched,
+/// as they were before and after the edit, alongside the changes it brought to the modification.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub label: String,
+    tree: BTreeMap<String, (Option<PathInfo>, Option<PathInfo>)>,
+    delta: ModificationDelta
+}
+
+#[derive(Debug, Clone)]
+struct Recording {
+    label: String,
+    tree: BTreeMap<String, Option<PathInfo>>
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    done: Vec<Operation>,
+    undone: Vec<Operation>,
+    recording: Option<Recording>
+}
+
+fn restore(content: &mut BTreeMap<String, PathInfo>, root: &String, info: &Option<PathInfo>) {
+    match info {
+        Some(info) => { content.insert(root.clone(), info.clone()); }
+        None => { content.remove(root); }
+    }
+}
+
+impl History {
+    pub fn new() -> History { History::default() }
+
+    /// Starts recording an operation that is about to touch the provided paths of the workspace.
+    pub fn begin(&mut self, label: String, content: &BTreeMap<String, PathInfo>, modification: &mut Modification, paths: &[String]) {
+        let tree = paths.iter()
+            .map(|path| path.split("/").next().unwrap().to_string())
+            .map(|root| {
+                let info = content.get(&root).cloned();
+                (root, info)
+            })
+            .collect();
+        modification.record();
+        self.recording = Some(Recording { label, tree });
+    }
+
+    /// Ends the recording of the current operation and makes it the next one to be undone.
+    pub fn commit(&mut self, content: &BTreeMap<String, PathInfo>, modification: &mut Modification) {
+        let Some(recording) = self.recording.take() else { return };
+        let delta = modification.delta();
+        let tree = recording.tree.into_iter()
+            .map(|(root, before)| {
+                let after = content.get(&root).cloned();
+                (root, (before, after))
+            })
+            .collect();
+        self.done.push(Operation { label: recording.label, tree, delta });
+        self.undone.clear();
+    }
+
+    pub fn undo(&mut self, content: &mut BTreeMap<String, PathInfo>, modification: &mut Modification) -> Option<String> {
+        let operation = self.done.pop()?;
+        for (root, (before, _)) in &operation.tree {
+            restore(content, root, before);
+        }
+        modification.rewind(&operation.delta);
+        let label = operation.label.clone();
+        self.undone.push(operation);
+        Some(label)
+    }
+
+    pub fn redo(&mut self, content: &mut BTreeMap<String, PathInfo>, modification: &mut Modification) -> Option<String> {
+        let operation = self.undone.pop()?;
+        for (root, (_, after)) in &operation.tree {
+            restore(content, root, after);
+        }
+        modification.replay(&operation.delta);
+        let label = operation.label.clone();
+        self.done.push(operation);
+        Some(label)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    pub fn next_undo(&self) -> Option<&String> {
+        self.done.last().map(|operation| &operation.label)
+    }
+
+    pub fn next_redo(&self) -> Option<&String> {
+        self.undone.last().map(|operation| &operation.label)
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+        self.recording = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::{self, FileContent};
+
+    /// Records the assignment of content to a path, creating it in the tree if needed.
+    fn assign(history: &mut History, content: &mut BTreeMap<String, PathInfo>, modification: &mut Modification, path: &str, bytes: &[u8]) {
+        history.begin(format!("Edit {}", path), content, modification, &[path.to_string()]);
+        workspace::append_workspace_content(content, vec![path.to_string()]);
+        modification.set(path.to_string(), FileContent::Bytes(bytes.to_vec()));
+        history.commit(content, modification);
+    }
+
+    fn bytes<'a>(modification: &'a Modification, path: &str) -> Option<&'a [u8]> {
+        match modification.view(&path.to_string()) {
+            Some(FileContent::Bytes(bytes)) => Some(bytes),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn empty_history_has_nothing_to_undo_or_redo() {
+        let (mut history, mut content, mut modification) = (History::new(), BTreeMap::new(), Modification::new());
+        assert!(!history.can_undo() && !history.can_redo());
+        assert_eq!(history.undo(&mut content, &mut modification), None);
+        assert_eq!(history.redo(&mut content, &mut modification), None);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_tree_and_the_modification() {
+        let (mut history, mut content, mut modification) = (History::new(), BTreeMap::new(), Modification::new());
+        assign(&mut history, &mut content, &mut modification, "a/stone.png", b"stone");
+        assert_eq!(history.next_undo(), Some(&"Edit a/stone.png".to_string()));
+        assert_eq!(history.undo(&mut content, &mut modification), Some("Edit a/stone.png".to_string()));
+        assert!(workspace::find_content(&content, "a/stone.png").is_none());
+        assert!(!content.contains_key("a"));
+        assert!(!modification.present());
+        assert!(!history.can_undo() && history.can_redo());
+        assert_eq!(history.redo(&mut content, &mut modification), Some("Edit a/stone.png".to_string()));
+        assert!(workspace::find_content(&content, "a/stone.png").is_some());
+        assert_eq!(bytes(&modification, "a/stone.png"), Some(&b"stone"[..]));
+        assert!(history.can_undo() && !history.can_redo());
+    }
+
+    #[test]
+    fn operations_are_undone_in_reverse_order() {
+        let (mut history, mut content, mut modification) = (History::new(), BTreeMap::new(), Modification::new());
+        assign(&mut history, &mut content, &mut modification, "stone.png", b"first");
+        assign(&mut history, &mut content, &mut modification, "stone.png", b"second");
+        assign(&mut history, &mut content, &mut modification, "dirt.png", b"dirt");
+        history.undo(&mut content, &mut modification);
+        assert!(workspace::find_content(&content, "dirt.png").is_none());
+        assert_eq!(bytes(&modification, "stone.png"), Some(&b"second"[..]));
+        history.undo(&mut content, &mut modification);
+        assert_eq!(bytes(&modification, "stone.png"), Some(&b"first"[..]));
+        history.undo(&mut content, &mut modification);
+        assert!(!modification.present());
+        assert!(content.is_empty());
+        history.redo(&mut content, &mut modification);
+        history.redo(&mut content, &mut modification);
+        assert_eq!(bytes(&modification, "stone.png"), Some(&b"second"[..]));
+        assert!(workspace::find_content(&content, "dirt.png").is_none());
+        assert_eq!(history.next_redo(), Some(&"Edit dirt.png".to_string()));
+    }
+
+    #[test]
+    fn new_operations_clear_the_redo_stack() {
+        let (mut history, mut content, mut modification) = (History::new(), BTreeMap::new(), Modification::new());
+        assign(&mut history, &mut content, &mut modification, "stone.png", b"first");
+        assign(&mut history, &mut content, &mut modification, "stone.png", b"second");
+        history.undo(&mut content, &mut modification);
+        assert!(history.can_redo());
+        assign(&mut history, &mut content, &mut modification, "dirt.png", b"dirt");
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(&mut content, &mut modification), None);
+        assert_eq!(bytes(&modification, "stone.png"), Some(&b"first"[..]));
+    }
+
+    #[test]
+    fn clearing_forgets_every_operation() {
+        let (mut history, mut content, mut modification) = (History::new(), BTreeMap::new(), Modification::new());
+        assign(&mut history, &mut content, &mut modification, "stone.png", b"first");
+        assign(&mut history, &mut content, &mut modification, "stone.png", b"second");
+        history.undo(&mut content, &mut modification);
+        history.clear();
+        assert!(!history.can_undo() && !history.can_redo());
+        assert_eq!(bytes(&modification, "stone.png"), Some(&b"first"[..]));
+    }
+}
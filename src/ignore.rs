@@ -0,0 +1,288 @@
+/// Files that operating systems and editors leave around, which never belong to a repository.
+const JUNK_PATTERNS: &str = "\
+.DS_Store
+._*
+__MACOSX/
+.Spotlight-V100/
+.Trashes/
+Thumbs.db
+ehthumbs.db
+desktop.ini
+$RECYCLE.BIN/
+*~
+.#*
+*.swp
+*.swo
+*.bak
+*.tmp
+";
+
+#[derive(Debug, Clone)]
+struct Rule {
+    base: String,
+    segments: Vec<String>,
+    anchored: bool,
+    negated: bool,
+    directory_only: bool
+}
+
+impl Rule {
+    fn parse(base: &str, line: &str) -> Option<Rule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with("#") {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix("!") {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix("\\").unwrap_or(line))
+        };
+        let (directory_only, line) = match line.strip_suffix("/") {
+            Some(rest) => (true, rest),
+            None => (false, line)
+        };
+        let anchored = line.contains("/");
+        let line = line.strip_prefix("/").unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+        Some(Rule {
+            base: base.to_string(),
+            segments: line.split("/").map(|segment| segment.to_string()).collect(),
+            anchored,
+            negated,
+            directory_only
+        })
+    }
+
+    fn matches(&self, path: &str, is_directory: bool) -> bool {
+        if self.directory_only && !is_directory {
+            return false;
+        }
+        let relative = if self.base.is_empty() {
+            path
+        } else {
+            match path.strip_prefix(&self.base).and_then(|rest| rest.strip_prefix("/")) {
+                Some(relative) => relative,
+                None => return false
+            }
+        };
+        let path_segments = relative.split("/").collect::<Vec<&str>>();
+        if self.anchored {
+            let pattern = self.segments.iter().map(|segment| segment.as_str()).collect::<Vec<&str>>();
+            match_segments(&pattern, &path_segments)
+        } else {
+            glob(&self.segments[0], path_segments.last().unwrap())
+        }
+    }
+}
+
+/// Ignore rules following the .gitignore syntax, where the last matching rule wins and where
+/// nothing inside an ignored directory can be included back.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>
+}
+
+impl IgnoreRules {
+    pub fn new() -> IgnoreRules {
+        let mut rules = IgnoreRules::default();
+        rules.add("", JUNK_PATTERNS);
+        rules
+    }
+
+    /// Adds the patterns of a .gitignore file located in the provided base directory.
+    pub fn add(&mut self, base: &str, contents: &str) {
+        self.rules.extend(contents.lines().filter_map(|line| Rule::parse(base, line)));
+    }
+
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let segments = path.split("/").collect::<Vec<&str>>();
+        for i in 1..=segments.len() {
+            let candidate = segments[..i].join("/");
+            let verdict = self.rules.iter().rev()
+                .find(|rule| rule.matches(&candidate, i < segments.len()))
+                .map(|rule| !rule.negated);
+            if verdict == Some(true) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => (0..=path.len()).any(|skipped| match_segments(&pattern[1..], &path[skipped..])),
+        Some(segment) => !path.is_empty() && glob(segment, path[0]) && match_segments(&pattern[1..], &path[1..])
+    }
+}
+
+/// Matches a single path segment against a pattern supporting `*`, `?`, `[...]` classes and
+/// backslash escapes.
+fn glob(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+    glob_chars(&pattern, &text)
+}
+
+fn glob_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skipped| glob_chars(&pattern[1..], &text[skipped..])),
+        Some('?') => !text.is_empty() && glob_chars(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let Some(end) = pattern.iter().skip(2).position(|c| *c == ']').map(|position| position + 2) else {
+                return !text.is_empty() && text[0] == '[' && glob_chars(&pattern[1..], &text[1..]);
+            };
+            if text.is_empty() {
+                return false;
+            }
+            let mut class = &pattern[1..end];
+            let negated = matches!(class.first(), Some('!' | '^'));
+            if negated {
+                class = &class[1..];
+            }
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= text[0] && text[0] <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == text[0];
+                    i += 1;
+                }
+            }
+            matched != negated && glob_chars(&pattern[end + 1..], &text[1..])
+        }
+        Some('\\') if pattern.len() > 1 => !text.is_empty() && text[0] == pattern[1] && glob_chars(&pattern[2..], &text[1..]),
+        Some(c) => !text.is_empty() && text[0] == *c && glob_chars(&pattern[1..], &text[1..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gitignore(base: &str, contents: &str) -> IgnoreRules {
+        let mut rules = IgnoreRules::default();
+        rules.add(base, contents);
+        rules
+    }
+
+    #[test]
+    fn junk_files_are_ignored_by_default() {
+        let rules = IgnoreRules::new();
+        assert!(rules.is_ignored(".DS_Store"));
+        assert!(rules.is_ignored("assets/Thumbs.db"));
+        assert!(rules.is_ignored("__MACOSX/assets/stone.png"));
+        assert!(rules.is_ignored("assets/stone.png~"));
+        assert!(!rules.is_ignored("assets/stone.png"));
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let rules = gitignore("", "# comment\n\n   \n");
+        assert!(!rules.is_ignored("# comment"));
+        assert!(!rules.is_ignored("stone.png"));
+    }
+
+    #[test]
+    fn patterns_without_slash_match_at_any_depth() {
+        let rules = gitignore("", "*.psd\n");
+        assert!(rules.is_ignored("stone.psd"));
+        assert!(rules.is_ignored("a/b/stone.psd"));
+        assert!(!rules.is_ignored("a/b/stone.png"));
+    }
+
+    #[test]
+    fn patterns_with_slash_are_anchored() {
+        let rules = gitignore("", "/build\ndocs/*.md\n");
+        assert!(rules.is_ignored("build"));
+        assert!(rules.is_ignored("build/out.png"));
+        assert!(!rules.is_ignored("a/build/out.png"));
+        assert!(rules.is_ignored("docs/readme.md"));
+        assert!(!rules.is_ignored("a/docs/readme.md"));
+        assert!(!rules.is_ignored("docs/sub/readme.md"));
+    }
+
+    #[test]
+    fn double_stars_match_any_amount_of_directories() {
+        let rules = gitignore("", "**/cache\na/**/b.png\nlogs/**\n");
+        assert!(rules.is_ignored("cache"));
+        assert!(rules.is_ignored("x/y/cache/z.png"));
+        assert!(rules.is_ignored("a/b.png"));
+        assert!(rules.is_ignored("a/x/y/b.png"));
+        assert!(!rules.is_ignored("c/a/b.png"));
+        assert!(rules.is_ignored("logs/a/b.txt"));
+        assert!(!rules.is_ignored("x/logs/a.txt"));
+    }
+
+    #[test]
+    fn negations_include_files_back() {
+        let rules = gitignore("", "*.png\n!keep.png\n");
+        assert!(rules.is_ignored("a/stone.png"));
+        assert!(!rules.is_ignored("a/keep.png"));
+        let rules = gitignore("", "!keep.png\n*.png\n");
+        assert!(rules.is_ignored("a/keep.png"));
+    }
+
+    #[test]
+    fn negations_cannot_include_back_inside_ignored_directories() {
+        let rules = gitignore("", "out/\n!out/keep.png\n");
+        assert!(rules.is_ignored("out/keep.png"));
+        assert!(rules.is_ignored("a/out/stone.png"));
+    }
+
+    #[test]
+    fn trailing_slashes_only_match_directories() {
+        let rules = gitignore("", "temp/\n");
+        assert!(rules.is_ignored("temp/stone.png"));
+        assert!(rules.is_ignored("a/temp/stone.png"));
+        assert!(!rules.is_ignored("temp"));
+        assert!(!rules.is_ignored("a/temp"));
+    }
+
+    #[test]
+    fn rules_are_scoped_to_their_base_directory() {
+        let rules = gitignore("assets", "*.png\n/raw\n");
+        assert!(rules.is_ignored("assets/stone.png"));
+        assert!(rules.is_ignored("assets/sub/stone.png"));
+        assert!(!rules.is_ignored("stone.png"));
+        assert!(!rules.is_ignored("assets2/stone.png"));
+        assert!(rules.is_ignored("assets/raw/stone.txt"));
+        assert!(!rules.is_ignored("assets/sub/raw/stone.txt"));
+        assert!(!rules.is_ignored("raw/stone.txt"));
+    }
+
+    #[test]
+    fn character_classes_match_a_single_character() {
+        let rules = gitignore("", "[abc].png\nimg[0-9].png\n[!a-y]*.txt\n");
+        assert!(rules.is_ignored("a.png"));
+        assert!(!rules.is_ignored("d.png"));
+        assert!(!rules.is_ignored("ab.png"));
+        assert!(rules.is_ignored("img5.png"));
+        assert!(!rules.is_ignored("imgx.png"));
+        assert!(rules.is_ignored("zebra.txt"));
+        assert!(!rules.is_ignored("apple.txt"));
+    }
+
+    #[test]
+    fn question_marks_match_exactly_one_character() {
+        let rules = gitignore("", "stone?.png\n");
+        assert!(rules.is_ignored("stone1.png"));
+        assert!(!rules.is_ignored("stone.png"));
+        assert!(!rules.is_ignored("stone12.png"));
+    }
+
+    #[test]
+    fn escapes_match_literally() {
+        let rules = gitignore("", "\\#notes\n\\!important\na\\*b\n[abc\n");
+        assert!(rules.is_ignored("#notes"));
+        assert!(rules.is_ignored("!important"));
+        assert!(rules.is_ignored("a*b"));
+        assert!(!rules.is_ignored("axb"));
+        assert!(rules.is_ignored("[abc"));
+    }
+}
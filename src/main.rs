@@ -1,9 +1,14 @@
 #![windows_subsystem = "windows"]
 use iced;
 use iced::{Font, Size};
-use crate::app::IllusionnaApp;
+use crate::app::{IllusionnaApp, APP_FONT};
 
 mod app;
+mod audio;
+mod diff;
+mod history;
+mod ignore;
+mod viewer;
 mod workspace;
 mod wrapper;
 
@@ -12,8 +17,8 @@ fn main() -> octocrab::Result<()> {
         .window_size(Size::new(854f32, 480f32))
         .resizable(false)
         .font(include_bytes!("../resources/inter.ttf").as_slice())
-        .default_font(Font::with_name("Inter 24pt"))
-        .subscription(IllusionnaApp::ticker)
+        .default_font(Font::with_name(APP_FONT))
+        .subscription(IllusionnaApp::subscription)
         .run_with(IllusionnaApp::new)
         .unwrap();
     Ok(())
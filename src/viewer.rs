@@ -0,0 +1,817 @@
+use ::image::{AnimationDecoder, ColorType, GenericImageView, ImageFormat as DecodedFormat, Rgba, RgbaImage};
+use serde::Deserialize;
+use iced::advanced::text::Highlighter as _;
+use iced::highlighter::{Highlighter, Settings, Theme};
+use iced::widget::text::Span;
+use iced::widget::{image, svg};
+use std::io::Cursor;
+use std::time::Duration;
+
+/// Lines highlighted at most, as larger files would make every layout of the viewer slow.
+const MAX_LINES: usize = 5000;
+
+/// Bytes shown at most by the hexadecimal preview of binary files.
+const MAX_HEX_BYTES: usize = 4096;
+
+/// A text file prepared for the viewer, highlighted once when it is opened. The spans carry the
+/// message type of the view displaying them.
+#[derive(Debug, Clone)]
+pub struct TextPreview<Link> {
+    pub line_numbers: String,
+    pub spans: Vec<Span<'static, Link>>,
+    pub total_lines: usize
+}
+
+impl<Link> TextPreview<Link> {
+    pub fn is_truncated(&self) -> bool {
+        self.total_lines > MAX_LINES
+    }
+
+    pub fn shown_lines(&self) -> usize {
+        self.total_lines.min(MAX_LINES)
+    }
+}
+
+/// Files are considered as text when they are valid UTF-8 without any null byte.
+pub fn is_text(bytes: &[u8]) -> bool {
+    !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok()
+}
+
+/// Finds the syntax used to highlight a file from its name, mapping the formats of resource
+/// packs to the closest syntax known by the highlighter.
+pub fn syntax_token(name: &str) -> String {
+    let extension = name.rsplit_once(".").map(|(_, extension)| extension.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "mcmeta" | "jsonc" | "json5" => "json".to_string(),
+        "lang" | "cfg" => "properties".to_string(),
+        "fsh" | "vsh" | "glsl" => "c".to_string(),
+        _ => extension
+    }
+}
+
+/// Highlights a text file, returning None when the file is not text.
+pub fn text_preview<Link>(name: &str, bytes: &[u8]) -> Option<TextPreview<Link>> {
+    if !is_text(bytes) {
+        return None;
+    }
+    let text = std::str::from_utf8(bytes).unwrap();
+    let mut highlighter = Highlighter::new(&Settings { theme: Theme::Base16Ocean, token: syntax_token(name) });
+    let mut spans = vec![];
+    let mut line_numbers = String::new();
+    let mut total_lines = 0;
+    for (i, line) in text.lines().enumerate() {
+        total_lines += 1;
+        if i >= MAX_LINES {
+            continue;
+        }
+        if i > 0 {
+            spans.push(Span::new("\n"));
+            line_numbers.push('\n');
+        }
+        line_numbers.push_str(&(i + 1).to_string());
+        let mut end = 0;
+        for (range, highlight) in highlighter.highlight_line(line) {
+            if range.start > end {
+                spans.push(Span::new(line[end..range.start].to_string()));
+            }
+            spans.push(Span::new(line[range.clone()].to_string()).color_maybe(highlight.color()));
+            end = range.end;
+        }
+        if end < line.len() {
+            spans.push(Span::new(line[end..].to_string()));
+        }
+    }
+    Some(TextPreview { line_numbers, spans, total_lines })
+}
+
+/// Formats the beginning of a binary file as offsets, hexadecimal bytes and printable characters.
+pub fn hex_preview(bytes: &[u8]) -> String {
+    bytes[..bytes.len().min(MAX_HEX_BYTES)].chunks(16).enumerate()
+        .map(|(i, chunk)| {
+            let hex = chunk.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<String>>().join(" ");
+            let ascii = chunk.iter().map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' }).collect::<String>();
+            format!("{:08x}  {:<47}  {}", i * 16, hex, ascii)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Image formats recognized by their content rather than by the extension of their name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    WebP,
+    Bmp,
+    Tga,
+    Svg
+}
+
+impl std::fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::WebP => "WebP",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::Tga => "TGA",
+            ImageFormat::Svg => "SVG"
+        })
+    }
+}
+
+/// Recognizes images from their magic bytes. TGA files only have a signature since version 2,
+/// so older ones are still recognized by their extension.
+pub fn sniff_image(name: &str, bytes: &[u8]) -> Option<ImageFormat> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(ImageFormat::Png)
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(ImageFormat::Jpeg)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(ImageFormat::Gif)
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some(ImageFormat::WebP)
+    } else if is_text(bytes) {
+        is_svg(bytes).then_some(ImageFormat::Svg)
+    } else if bytes.starts_with(b"BM") && bytes.len() > 26 {
+        Some(ImageFormat::Bmp)
+    } else if bytes.ends_with(b"TRUEVISION-XFILE.\0") || name.to_lowercase().ends_with(".tga") {
+        Some(ImageFormat::Tga)
+    } else {
+        None
+    }
+}
+
+/// SVG files are XML documents whose root element is svg, which may come after an XML declaration,
+/// a doctype, comments or processing instructions.
+fn is_svg(bytes: &[u8]) -> bool {
+    // The prefix may end in the middle of a character, which is harmless once replaced.
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]);
+    let mut rest = text.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        let end = if rest.starts_with("<?") {
+            rest.find("?>").map(|end| end + 2)
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("<!") {
+            // A doctype can declare entities between brackets, which contain closing angle brackets.
+            match (rest.find('['), rest.find('>')) {
+                (Some(bracket), Some(end)) if bracket < end => rest.find("]>").map(|end| end + 2),
+                (_, end) => end.map(|end| end + 1)
+            }
+        } else {
+            break;
+        };
+        let Some(end) = end else { return false };
+        rest = rest[end..].trim_start();
+    }
+    rest.strip_prefix("<svg").and_then(|rest| rest.chars().next()).is_some_and(|c| c.is_whitespace() || matches!(c, '>' | '/' | ':'))
+}
+
+/// What artists need to know about a raster image. The bit depth and the color type are the
+/// ones stored in the file, which for PNG can be paletted even though it is decoded as RGB.
+#[derive(Debug, Clone)]
+pub struct ImageMetadata {
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: u8,
+    pub color_type: String,
+    pub alpha: bool,
+    pub translucent: bool
+}
+
+impl ImageMetadata {
+    fn new(format: ImageFormat, bytes: &[u8], color: ColorType, pixels: &RgbaImage) -> ImageMetadata {
+        let (bit_depth, color_type) = match (format, bytes.get(24..26)) {
+            (ImageFormat::Png, Some(&[depth, kind])) => (depth, match kind {
+                0 => "Grayscale",
+                2 => "RGB",
+                3 => "Indexed",
+                4 => "Grayscale + Alpha",
+                _ => "RGBA"
+            }.to_string()),
+            _ => ((color.bits_per_pixel() / color.channel_count() as u16) as u8, color_name(color).to_string())
+        };
+        ImageMetadata {
+            format,
+            width: pixels.width(),
+            height: pixels.height(),
+            bit_depth,
+            color_type,
+            alpha: color.has_alpha(),
+            translucent: color.has_alpha() && pixels.pixels().any(|pixel| pixel[3] < 255)
+        }
+    }
+}
+
+fn color_name(color: ColorType) -> &'static str {
+    match color {
+        ColorType::L8 | ColorType::L16 => "Grayscale",
+        ColorType::La8 | ColorType::La16 => "Grayscale + Alpha",
+        ColorType::Rgb8 | ColorType::Rgb16 | ColorType::Rgb32F => "RGB",
+        _ => "RGBA"
+    }
+}
+
+/// An image prepared for the viewer, raster images being decoded into their frames.
+#[derive(Debug, Clone)]
+pub enum ImagePreview {
+    Raster {
+        metadata: ImageMetadata,
+        frames: Vec<(image::Handle, Duration)>
+    },
+    Vector(svg::Handle)
+}
+
+impl ImagePreview {
+    pub fn is_animated(&self) -> bool {
+        matches!(self, ImagePreview::Raster { frames, .. } if frames.len() > 1)
+    }
+
+    /// The pixels of a still raster image, read back from its handle rather than decoded again.
+    pub fn pixels(&self) -> Option<RgbaImage> {
+        let ImagePreview::Raster { frames, .. } = self else { return None };
+        match &frames.first()?.0 {
+            image::Handle::Rgba { width, height, pixels, .. } => RgbaImage::from_raw(*width, *height, pixels.to_vec()),
+            _ => None
+        }
+    }
+
+    /// The frame shown after some time of playback, looping over the animation.
+    pub fn frame_at(&self, elapsed: Duration) -> Option<&image::Handle> {
+        let ImagePreview::Raster { frames, .. } = self else { return None };
+        let total = frames.iter().map(|(_, delay)| delay.as_millis()).sum::<u128>().max(1);
+        let mut remaining = elapsed.as_millis() % total;
+        for (frame, delay) in frames {
+            if remaining < delay.as_millis() {
+                return Some(frame);
+            }
+            remaining -= delay.as_millis();
+        }
+        frames.last().map(|(frame, _)| frame)
+    }
+}
+
+fn decoded_format(format: ImageFormat) -> Option<DecodedFormat> {
+    match format {
+        ImageFormat::Svg => None,
+        ImageFormat::Png => Some(DecodedFormat::Png),
+        ImageFormat::Jpeg => Some(DecodedFormat::Jpeg),
+        ImageFormat::Gif => Some(DecodedFormat::Gif),
+        ImageFormat::WebP => Some(DecodedFormat::WebP),
+        ImageFormat::Bmp => Some(DecodedFormat::Bmp),
+        ImageFormat::Tga => Some(DecodedFormat::Tga)
+    }
+}
+
+/// Reads the dimensions of a raster image without decoding its pixels.
+pub fn image_dimensions(name: &str, bytes: &[u8]) -> Option<(u32, u32)> {
+    let format = decoded_format(sniff_image(name, bytes)?)?;
+    ::image::io::Reader::with_format(Cursor::new(bytes), format).into_dimensions().ok()
+}
+
+/// Decodes an image recognized by its content, returning None when it is not an image or when
+/// it cannot be decoded.
+pub fn image_preview(name: &str, bytes: &[u8]) -> Option<ImagePreview> {
+    let format = sniff_image(name, bytes)?;
+    let Some(decoded_format) = decoded_format(format) else {
+        return Some(ImagePreview::Vector(svg::Handle::from_memory(bytes.to_vec())));
+    };
+    let animation = match format {
+        ImageFormat::Gif => ::image::codecs::gif::GifDecoder::new(Cursor::new(bytes)).ok().map(|decoder| decoder.into_frames()),
+        ImageFormat::WebP => ::image::codecs::webp::WebPDecoder::new(Cursor::new(bytes)).ok()
+            .filter(|decoder| decoder.has_animation())
+            .map(|decoder| decoder.into_frames()),
+        _ => None
+    };
+    let frames = animation
+        .and_then(|frames| frames.collect_frames().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|frame| {
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            // Like browsers do, frames without a meaningful delay are shown for a tenth of a second.
+            let delay = match numerator / denominator.max(1) {
+                0..=10 => 100,
+                milliseconds => milliseconds
+            };
+            let buffer = frame.into_buffer();
+            (image::Handle::from_rgba(buffer.width(), buffer.height(), buffer.into_raw()), Duration::from_millis(delay as u64))
+        })
+        .collect::<Vec<(image::Handle, Duration)>>();
+    let decoded = ::image::load_from_memory_with_format(bytes, decoded_format).ok()?;
+    let color = decoded.color();
+    let pixels = decoded.into_rgba8();
+    let metadata = ImageMetadata::new(format, bytes, color, &pixels);
+    let frames = match frames.is_empty() {
+        true => vec![(image::Handle::from_rgba(metadata.width, metadata.height, pixels.into_raw()), Duration::ZERO)],
+        false => frames
+    };
+    Some(ImagePreview::Raster { metadata, frames })
+}
+
+/// Decodes an image on a blocking thread, as large textures take a while to decode.
+pub async fn decode_image(name: String, bytes: Vec<u8>) -> Option<ImagePreview> {
+    tokio::task::spawn_blocking(move || image_preview(&name, &bytes)).await.ok().flatten()
+}
+
+/// Duration of a game tick, the unit of the frame times of animated textures.
+pub const TICK: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Deserialize)]
+struct TextureMetadata {
+    animation: Option<AnimationSection>
+}
+
+/// The animation section of a .mcmeta sidecar file, describing how the frames stacked in the
+/// texture are played.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationSection {
+    #[serde(default = "default_frametime")]
+    frametime: u32,
+    #[serde(default)]
+    interpolate: bool,
+    width: Option<u32>,
+    height: Option<u32>,
+    #[serde(default)]
+    frames: Vec<AnimationFrame>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum AnimationFrame {
+    Index(usize),
+    Timed { index: usize, time: Option<u32> }
+}
+
+fn default_frametime() -> u32 {
+    1
+}
+
+/// Reads the animation section of a .mcmeta file, if it has one.
+pub fn parse_mcmeta(bytes: &[u8]) -> Option<AnimationSection> {
+    serde_json::from_slice::<TextureMetadata>(bytes).ok()?.animation
+}
+
+/// A texture split into its frames and played in the order of its .mcmeta file, where each
+/// frame lasts a number of ticks and may blend into the next one when interpolated.
+#[derive(Debug, Clone)]
+pub struct TextureAnimation {
+    sprites: Vec<RgbaImage>,
+    frames: Vec<(usize, u32)>,
+    interpolate: bool,
+    tick: u64,
+    pub current: image::Handle
+}
+
+impl TextureAnimation {
+    /// Splits a texture into frames, reading them from left to right and then from top to bottom.
+    /// Like in Minecraft, frames are square when their size is not given at all, while a single
+    /// missing side spans the whole texture.
+    pub fn new(texture: &RgbaImage, section: &AnimationSection) -> Option<TextureAnimation> {
+        let (width, height) = texture.dimensions();
+        let (frame_width, frame_height) = match (section.width, section.height) {
+            (Some(frame_width), Some(frame_height)) => (frame_width, frame_height),
+            (Some(frame_width), None) => (frame_width, height),
+            (None, Some(frame_height)) => (width, frame_height),
+            (None, None) => (width.min(height), width.min(height))
+        };
+        if frame_width == 0 || frame_height == 0 {
+            return None;
+        }
+        let columns = width / frame_width;
+        let count = (columns * (height / frame_height)) as usize;
+        let sprites = (0..count as u32)
+            .map(|i| texture.view((i % columns) * frame_width, (i / columns) * frame_height, frame_width, frame_height).to_image())
+            .collect::<Vec<RgbaImage>>();
+        // Frames last at least a tick, otherwise the animation would have no length to loop over.
+        let frametime = section.frametime.max(1);
+        let frames = match section.frames.is_empty() {
+            true => (0..count).map(|i| (i, frametime)).collect::<Vec<(usize, u32)>>(),
+            false => section.frames.iter()
+                .map(|frame| match frame {
+                    AnimationFrame::Index(index) => (*index, frametime),
+                    AnimationFrame::Timed { index, time } => (*index, time.unwrap_or(frametime))
+                })
+                .filter(|(index, _)| *index < count)
+                .map(|(index, time)| (index, time.max(1)))
+                .collect()
+        };
+        let first = sprites.get(frames.first()?.0)?;
+        let current = handle(first);
+        Some(TextureAnimation { sprites, frames, interpolate: section.interpolate, tick: 0, current })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    fn total_ticks(&self) -> u64 {
+        self.frames.iter().map(|(_, time)| *time as u64).sum()
+    }
+
+    /// The position in the frame order shown at the current tick, and the ticks spent on it.
+    pub fn position(&self) -> (usize, u32) {
+        let mut remaining = self.tick;
+        for (position, (_, time)) in self.frames.iter().enumerate() {
+            if remaining < *time as u64 {
+                return (position, remaining as u32);
+            }
+            remaining -= *time as u64;
+        }
+        (0, 0)
+    }
+
+    /// Moves to a tick of the animation, looping over it, and redraws the shown frame.
+    pub fn seek(&mut self, tick: u64) {
+        let total = self.total_ticks();
+        if total == 0 {
+            return;
+        }
+        let tick = tick % total;
+        if tick == self.tick {
+            return;
+        }
+        let previous = self.position();
+        self.tick = tick;
+        let (position, elapsed) = self.position();
+        let (sprite, time) = self.frames[position];
+        if self.interpolate && elapsed > 0 {
+            let next = &self.sprites[self.frames[(position + 1) % self.frames.len()].0];
+            let progress = elapsed as f32 / time as f32;
+            let blended = RgbaImage::from_fn(next.width(), next.height(), |x, y| {
+                let (from, to) = (self.sprites[sprite].get_pixel(x, y), next.get_pixel(x, y));
+                Rgba(std::array::from_fn(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * progress).round() as u8))
+            });
+            self.current = handle(&blended);
+        } else if previous.0 != position || (self.interpolate && previous.1 > 0) {
+            self.current = handle(&self.sprites[sprite]);
+        }
+    }
+
+    /// Moves to the start of the next or the previous frame.
+    pub fn step(&mut self, forward: bool) {
+        let (position, _) = self.position();
+        let count = self.frames.len();
+        let target = if forward { (position + 1) % count } else { (position + count - 1) % count };
+        let start = self.frames[..target].iter().map(|(_, time)| *time as u64).sum::<u64>();
+        self.seek(start);
+    }
+}
+
+fn handle(pixels: &RgbaImage) -> image::Handle {
+    image::Handle::from_rgba(pixels.width(), pixels.height(), pixels.as_raw().clone())
+}
+
+/// Unicode blocks checked by the font viewer, covering the languages Minecraft is translated to
+/// with Latin, Greek and Cyrillic scripts, along with the symbols resource packs commonly use.
+const UNICODE_BLOCKS: [(&str, char, char); 10] = [
+    ("Basic Latin", '\u{0020}', '\u{007e}'),
+    ("Latin-1 Supplement", '\u{00a0}', '\u{00ff}'),
+    ("Latin Extended-A", '\u{0100}', '\u{017f}'),
+    ("Latin Extended-B", '\u{0180}', '\u{024f}'),
+    ("Greek and Coptic", '\u{0370}', '\u{03ff}'),
+    ("Cyrillic", '\u{0400}', '\u{04ff}'),
+    ("General Punctuation", '\u{2000}', '\u{206f}'),
+    ("Currency Symbols", '\u{20a0}', '\u{20c0}'),
+    ("Arrows", '\u{2190}', '\u{21ff}'),
+    ("Box Drawing", '\u{2500}', '\u{257f}')
+];
+
+/// How many characters of a Unicode block have a glyph in a font.
+#[derive(Debug, Clone)]
+pub struct BlockCoverage {
+    pub name: &'static str,
+    pub covered: usize,
+    pub total: usize
+}
+
+/// What the font viewer shows about a font, read from its tables. The family is the name the
+/// text renderer knows the font by once it is loaded, the typographic family when there is one.
+#[derive(Debug, Clone)]
+pub struct FontMetadata {
+    pub family: String,
+    pub subfamily: Option<String>,
+    pub version: Option<String>,
+    pub outlines: &'static str,
+    pub weight: u16,
+    pub italic: bool,
+    pub monospaced: bool,
+    pub variable: bool,
+    pub glyph_count: u16,
+    pub coverage: Vec<BlockCoverage>,
+    pub missing_ascii: String
+}
+
+/// Recognizes TrueType and OpenType fonts, as well as font collections, from their magic bytes.
+pub fn is_font(bytes: &[u8]) -> bool {
+    [b"\x00\x01\x00\x00", b"OTTO", b"true", b"ttcf"].iter().any(|magic| bytes.starts_with(*magic))
+}
+
+fn font_name(face: &ttf_parser::Face, ids: &[u16]) -> Option<String> {
+    ids.iter().find_map(|id| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == *id)
+            .find_map(|name| name.to_string())
+            .filter(|name| !name.trim().is_empty())
+    })
+}
+
+/// Reads the names and the glyph coverage of a font, returning None when it is not a font. Only
+/// the first font of a collection is described.
+pub fn font_metadata(bytes: &[u8]) -> Option<FontMetadata> {
+    if !is_font(bytes) {
+        return None;
+    }
+    let face = ttf_parser::Face::parse(bytes, 0).ok()?;
+    let coverage = UNICODE_BLOCKS.iter().map(|(name, first, last)| {
+        let characters = (*first..=*last).collect::<Vec<char>>();
+        BlockCoverage {
+            name: *name,
+            covered: characters.iter().filter(|character| face.glyph_index(**character).is_some()).count(),
+            total: characters.len()
+        }
+    }).collect();
+    Some(FontMetadata {
+        family: font_name(&face, &[ttf_parser::name_id::TYPOGRAPHIC_FAMILY, ttf_parser::name_id::FAMILY])?,
+        subfamily: font_name(&face, &[ttf_parser::name_id::TYPOGRAPHIC_SUBFAMILY, ttf_parser::name_id::SUBFAMILY]),
+        version: font_name(&face, &[ttf_parser::name_id::VERSION]),
+        outlines: if face.tables().cff.is_some() || face.tables().cff2.is_some() { "OpenType (CFF)" } else { "TrueType" },
+        weight: face.weight().to_number(),
+        italic: !matches!(face.style(), ttf_parser::Style::Normal),
+        monospaced: face.is_monospaced(),
+        variable: face.is_variable(),
+        glyph_count: face.number_of_glyphs(),
+        coverage,
+        missing_ascii: ('\u{0021}'..='\u{007e}').filter(|character| face.glyph_index(*character).is_none()).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(json: &str) -> AnimationSection {
+        parse_mcmeta(json.as_bytes()).unwrap()
+    }
+
+    /// Builds a texture of frames stacked from left to right then from top to bottom, each one
+    /// filled with its index as red.
+    fn texture(width: u32, height: u32, frame_width: u32, frame_height: u32) -> RgbaImage {
+        let columns = width / frame_width;
+        RgbaImage::from_fn(width, height, |x, y| Rgba([((y / frame_height) * columns + x / frame_width) as u8 * 10, 0, 0, 255]))
+    }
+
+    /// The index of the sprite currently shown, read back from the red of its first pixel.
+    fn shown(animation: &TextureAnimation) -> u8 {
+        match &animation.current {
+            image::Handle::Rgba { pixels, .. } => pixels[0] / 10,
+            _ => panic!("the current frame is not made of pixels")
+        }
+    }
+
+    #[test]
+    fn mcmeta_defaults_are_filled_in() {
+        let section = section(r#"{"animation": {}}"#);
+        assert_eq!(section.frametime, 1);
+        assert!(!section.interpolate);
+        assert_eq!((section.width, section.height), (None, None));
+        assert!(section.frames.is_empty());
+    }
+
+    #[test]
+    fn mcmeta_frames_are_read_with_or_without_times() {
+        let section = section(r#"{"animation": {"frametime": 5, "interpolate": true, "width": 16, "height": 8, "frames": [2, {"index": 0, "time": 10}, {"index": 1}]}}"#);
+        assert_eq!(section.frametime, 5);
+        assert!(section.interpolate);
+        assert_eq!((section.width, section.height), (Some(16), Some(8)));
+        assert!(matches!(section.frames[..], [
+            AnimationFrame::Index(2),
+            AnimationFrame::Timed { index: 0, time: Some(10) },
+            AnimationFrame::Timed { index: 1, time: None }
+        ]));
+    }
+
+    #[test]
+    fn mcmeta_without_animation_is_ignored() {
+        assert!(parse_mcmeta(br#"{"texture": {"blur": true}}"#).is_none());
+        assert!(parse_mcmeta(b"{").is_none());
+        assert!(parse_mcmeta(b"").is_none());
+    }
+
+    #[test]
+    fn frames_are_square_without_size() {
+        let animation = TextureAnimation::new(&texture(16, 48, 16, 16), &section(r#"{"animation": {}}"#)).unwrap();
+        assert_eq!(animation.frame_count(), 3);
+        assert_eq!(animation.sprites[0].dimensions(), (16, 16));
+    }
+
+    #[test]
+    fn a_single_given_side_spans_the_other_one() {
+        let animation = TextureAnimation::new(&texture(16, 32, 8, 32), &section(r#"{"animation": {"width": 8}}"#)).unwrap();
+        assert_eq!(animation.frame_count(), 2);
+        assert_eq!(animation.sprites[0].dimensions(), (8, 32));
+        let animation = TextureAnimation::new(&texture(32, 48, 32, 16), &section(r#"{"animation": {"height": 16}}"#)).unwrap();
+        assert_eq!(animation.frame_count(), 3);
+        assert_eq!(animation.sprites[0].dimensions(), (32, 16));
+    }
+
+    #[test]
+    fn frames_are_read_by_rows() {
+        let mut animation = TextureAnimation::new(&texture(16, 16, 8, 8), &section(r#"{"animation": {"width": 8, "height": 8}}"#)).unwrap();
+        assert_eq!(animation.frame_count(), 4);
+        for sprite in 0..4 {
+            assert_eq!(shown(&animation), sprite);
+            animation.step(true);
+        }
+        assert_eq!(shown(&animation), 0);
+    }
+
+    #[test]
+    fn frames_follow_the_mcmeta_order_and_times() {
+        let section = section(r#"{"animation": {"frametime": 2, "frames": [2, {"index": 0, "time": 3}, 7, {"index": 1, "time": 0}]}}"#);
+        let animation = TextureAnimation::new(&texture(8, 24, 8, 8), &section).unwrap();
+        assert_eq!(animation.frames, vec![(2, 2), (0, 3), (1, 1)]);
+        assert_eq!(shown(&animation), 2);
+    }
+
+    #[test]
+    fn textures_without_any_playable_frame_are_rejected() {
+        assert!(TextureAnimation::new(&texture(8, 8, 8, 8), &section(r#"{"animation": {"frames": [3]}}"#)).is_none());
+        assert!(TextureAnimation::new(&texture(8, 8, 8, 8), &section(r#"{"animation": {"width": 0}}"#)).is_none());
+        assert!(TextureAnimation::new(&texture(8, 8, 8, 8), &section(r#"{"animation": {"width": 16, "height": 16}}"#)).is_none());
+    }
+
+    #[test]
+    fn position_counts_ticks_within_frames() {
+        let section = section(r#"{"animation": {"frames": [{"index": 0, "time": 2}, {"index": 1, "time": 3}]}}"#);
+        let mut animation = TextureAnimation::new(&texture(8, 16, 8, 8), &section).unwrap();
+        assert_eq!(animation.position(), (0, 0));
+        animation.seek(1);
+        assert_eq!(animation.position(), (0, 1));
+        animation.seek(2);
+        assert_eq!(animation.position(), (1, 0));
+        assert_eq!(shown(&animation), 1);
+        animation.seek(4);
+        assert_eq!(animation.position(), (1, 2));
+    }
+
+    #[test]
+    fn seeking_loops_over_the_animation() {
+        let section = section(r#"{"animation": {"frames": [{"index": 0, "time": 2}, {"index": 1, "time": 3}]}}"#);
+        let mut animation = TextureAnimation::new(&texture(8, 16, 8, 8), &section).unwrap();
+        animation.seek(6);
+        assert_eq!((animation.tick(), animation.position()), (1, (0, 1)));
+        assert_eq!(shown(&animation), 0);
+        animation.seek(12);
+        assert_eq!((animation.tick(), animation.position()), (2, (1, 0)));
+        assert_eq!(shown(&animation), 1);
+        animation.seek(5);
+        assert_eq!(animation.tick(), 0);
+        assert_eq!(shown(&animation), 0);
+    }
+
+    #[test]
+    fn stepping_wraps_around_both_ends() {
+        let mut animation = TextureAnimation::new(&texture(8, 24, 8, 8), &section(r#"{"animation": {"frametime": 4}}"#)).unwrap();
+        animation.step(false);
+        assert_eq!((animation.tick(), shown(&animation)), (8, 2));
+        animation.step(true);
+        assert_eq!((animation.tick(), shown(&animation)), (0, 0));
+    }
+
+    #[test]
+    fn interpolation_blends_into_the_next_frame() {
+        let mut animation = TextureAnimation::new(&texture(8, 16, 8, 8), &section(r#"{"animation": {"frametime": 4, "interpolate": true}}"#)).unwrap();
+        animation.seek(2);
+        let image::Handle::Rgba { pixels, .. } = &animation.current else { panic!() };
+        assert_eq!(pixels[0], 5);
+    }
+
+    fn png(image: ::image::DynamicImage) -> Vec<u8> {
+        let mut bytes = vec![];
+        image.write_to(&mut Cursor::new(&mut bytes), DecodedFormat::Png).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn images_are_sniffed_from_their_magic_bytes() {
+        assert_eq!(sniff_image("a", &png(::image::DynamicImage::new_rgba8(1, 1))), Some(ImageFormat::Png));
+        assert_eq!(sniff_image("a", &[0xFF, 0xD8, 0xFF, 0xE0]), Some(ImageFormat::Jpeg));
+        assert_eq!(sniff_image("a", b"GIF89a\x01\x00"), Some(ImageFormat::Gif));
+        assert_eq!(sniff_image("a", b"RIFF\x00\x00\x00\x00WEBPVP8 "), Some(ImageFormat::WebP));
+        assert_eq!(sniff_image("a", b"RIFF\x00\x00\x00\x00WAVEfmt "), None);
+        assert_eq!(sniff_image("a", &[b"BM".as_slice(), &[0; 30]].concat()), Some(ImageFormat::Bmp));
+        assert_eq!(sniff_image("a.bmp", b"BM"), None);
+        assert_eq!(sniff_image("a.svg", b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), Some(ImageFormat::Svg));
+        assert_eq!(sniff_image("a.txt", b"BM is a text file after all"), None);
+    }
+
+    #[test]
+    fn tga_files_are_sniffed_from_their_footer_or_their_name() {
+        let tga = [&[0u8; 20][..], b"TRUEVISION-XFILE.\0"].concat();
+        assert_eq!(sniff_image("a", &tga), Some(ImageFormat::Tga));
+        assert_eq!(sniff_image("OLD.TGA", &[0, 0, 2, 0, 0xFF]), Some(ImageFormat::Tga));
+        assert_eq!(sniff_image("a.bin", &[0, 0, 2, 0, 0xFF]), None);
+    }
+
+    #[test]
+    fn svg_files_are_recognized_by_their_root_element() {
+        assert!(is_svg(b"<svg/>"));
+        assert!(is_svg(b"\xef\xbb\xbf\n  <svg width=\"16\">"));
+        assert!(is_svg(b"<svg:svg xmlns:svg=\"http://www.w3.org/2000/svg\">"));
+        assert!(is_svg(b"<?xml version=\"1.0\"?>\n<!-- <html> -->\n<?xml-stylesheet href=\"a.css\"?>\n<svg>"));
+        assert!(is_svg(b"<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \"svg11.dtd\">\n<svg>"));
+        assert!(is_svg(b"<!DOCTYPE svg [\n  <!ENTITY icon \"<g>\">\n]>\n<svg>&icon;</svg>"));
+    }
+
+    #[test]
+    fn documents_merely_containing_svg_are_not_svg_files() {
+        assert!(!is_svg(b"<html><body><svg/></body></html>"));
+        assert!(!is_svg(b"<svgfont/>"));
+        assert!(!is_svg(b"<!-- <svg> is only mentioned"));
+        assert!(!is_svg(b"<?xml version=\"1.0\"?>"));
+        assert!(!is_svg(b"svg"));
+        assert!(!is_svg(b""));
+    }
+
+    #[test]
+    fn png_metadata_is_read_from_the_header() {
+        let mut rgba = ::image::RgbaImage::new(3, 2);
+        rgba.put_pixel(1, 1, Rgba([0, 0, 0, 128]));
+        let Some(ImagePreview::Raster { metadata, frames }) = image_preview("a.png", &png(rgba.into())) else { panic!("the PNG was not decoded") };
+        assert_eq!((metadata.format, metadata.width, metadata.height), (ImageFormat::Png, 3, 2));
+        assert_eq!((metadata.bit_depth, metadata.color_type.as_str()), (8, "RGBA"));
+        assert!(metadata.alpha && metadata.translucent);
+        assert_eq!(frames.len(), 1);
+        let Some(ImagePreview::Raster { metadata, .. }) = image_preview("a.png", &png(::image::DynamicImage::new_luma16(1, 1))) else { panic!("the PNG was not decoded") };
+        assert_eq!((metadata.bit_depth, metadata.color_type.as_str()), (16, "Grayscale"));
+        assert!(!metadata.alpha && !metadata.translucent);
+    }
+
+    #[test]
+    fn paletted_pngs_keep_their_stored_color_type() {
+        let mut bytes = png(::image::DynamicImage::new_rgb8(1, 1));
+        bytes[24..26].copy_from_slice(&[4, 3]);
+        let metadata = ImageMetadata::new(ImageFormat::Png, &bytes, ColorType::Rgb8, &RgbaImage::new(1, 1));
+        assert_eq!((metadata.bit_depth, metadata.color_type.as_str()), (4, "Indexed"));
+    }
+
+    #[test]
+    fn other_formats_describe_their_decoded_color() {
+        let pixels = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255]));
+        let metadata = ImageMetadata::new(ImageFormat::Tga, b"", ColorType::La16, &pixels);
+        assert_eq!((metadata.bit_depth, metadata.color_type.as_str()), (16, "Grayscale + Alpha"));
+        assert!(metadata.alpha && !metadata.translucent);
+        let metadata = ImageMetadata::new(ImageFormat::Bmp, b"", ColorType::Rgb8, &pixels);
+        assert_eq!((metadata.bit_depth, metadata.color_type.as_str()), (8, "RGB"));
+    }
+
+    #[test]
+    fn hex_previews_align_their_columns() {
+        assert_eq!(hex_preview(b""), "");
+        assert_eq!(hex_preview(b"Hi \x00\xff"), format!("00000000  {:<47}  Hi ..", "48 69 20 00 ff"));
+        let lines = hex_preview(&[b'a'; 20]).lines().map(str::to_string).collect::<Vec<String>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], format!("00000000  {}  {}", ["61"; 16].join(" "), "a".repeat(16)));
+        assert!(lines[1].starts_with("00000010  61 61 61 61  "));
+    }
+
+    #[test]
+    fn hex_previews_stop_at_their_limit() {
+        let preview = hex_preview(&vec![0; MAX_HEX_BYTES * 2]);
+        assert_eq!(preview.lines().count(), MAX_HEX_BYTES / 16);
+        assert!(preview.lines().last().unwrap().starts_with(&format!("{:08x}", MAX_HEX_BYTES - 16)));
+    }
+
+    #[test]
+    fn font_metadata_is_read_from_the_tables() {
+        let metadata = font_metadata(include_bytes!("../resources/inter.ttf")).unwrap();
+        assert_eq!(metadata.family, "Inter 24pt");
+        assert_eq!(metadata.subfamily.as_deref(), Some("Regular"));
+        assert!(metadata.version.is_some_and(|version| version.starts_with("Version 4.001")));
+        assert_eq!((metadata.outlines, metadata.weight), ("TrueType", 400));
+        assert!(!metadata.italic && !metadata.monospaced && !metadata.variable);
+        assert_eq!(metadata.glyph_count, 2926);
+        assert_eq!(metadata.coverage.len(), UNICODE_BLOCKS.len());
+        assert_eq!(metadata.coverage[0].total, 95);
+        assert_eq!(metadata.coverage[0].covered, 95);
+        assert!(metadata.missing_ascii.is_empty());
+    }
+
+    #[test]
+    fn files_that_are_not_fonts_have_no_font_metadata() {
+        assert!(font_metadata(b"").is_none());
+        assert!(font_metadata(&png(::image::DynamicImage::new_rgba8(1, 1))).is_none());
+        assert!(font_metadata(b"OTTO but not really a font").is_none());
+        assert!(font_metadata(b"\x00\x01\x00\x00\x00\x00").is_none());
+    }
+}
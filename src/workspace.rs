@@ -1,11 +1,12 @@
 use crate::wrapper;
-use crate::wrapper::TreeCreationPart;
+use crate::wrapper::{CommitIdentity, TreeCreationPart, TreePart};
 use iced::widget::image;
 use octocrab;
 use octocrab::Octocrab;
-use std::collections::{BTreeMap, HashMap};
-use std::path::Path;
-use iced::futures::StreamExt;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use iced::futures::channel::mpsc::Sender;
+use iced::futures::{SinkExt, Stream, StreamExt};
 use normalize_path::NormalizePath;
 
 #[derive(Debug, Clone)]
@@ -81,7 +82,7 @@ pub async fn create_workspace(crab: Octocrab, info: WorkspaceInfo) {
     wrapper::sync_default_branch(&crab, &info.project.fork_owner, &info.project.fork_name).await;
     wrapper::create_branch(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id).await;
     let (branch, commit) = wrapper::create_empty_commit(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id).await.unwrap();
-    wrapper::push_commit(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id, &branch, &commit).await;
+    let _ = wrapper::push_commit(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id, &branch, &commit).await;
     wrapper::create_draft_pull_request(
         &crab,
         &info.project.source_owner,
@@ -97,9 +98,50 @@ pub struct PathInfo {
     pub sha: String,
     pub path: String,
     pub url: String,
+    pub mode: EntryMode,
     pub content: PathContent
 }
 
+/// The git mode of a tree entry, which also determines the type of the object it points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryMode {
+    File,
+    Executable,
+    Symlink,
+    Submodule,
+    Directory
+}
+
+impl EntryMode {
+    pub fn from_git(mode: &str, object_type: &str) -> EntryMode {
+        match (object_type, mode) {
+            ("tree", _) => EntryMode::Directory,
+            ("commit", _) => EntryMode::Submodule,
+            (_, "100755") => EntryMode::Executable,
+            (_, "120000") => EntryMode::Symlink,
+            _ => EntryMode::File
+        }
+    }
+
+    pub fn mode(&self) -> &'static str {
+        match self {
+            EntryMode::File => "100644",
+            EntryMode::Executable => "100755",
+            EntryMode::Symlink => "120000",
+            EntryMode::Submodule => "160000",
+            EntryMode::Directory => "040000"
+        }
+    }
+
+    pub fn object_type(&self) -> &'static str {
+        match self {
+            EntryMode::File | EntryMode::Executable | EntryMode::Symlink => "blob",
+            EntryMode::Submodule => "commit",
+            EntryMode::Directory => "tree"
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum PathContent {
     File(FileInfo),
@@ -114,26 +156,58 @@ pub struct FileInfo {
 #[derive(Debug, Clone)]
 pub struct DirectoryInfo {
     pub name: String,
-    pub contents: BTreeMap<String, PathInfo>
+    pub contents: BTreeMap<String, PathInfo>,
+    pub loaded: bool
 }
 
 impl PathInfo {
-    pub fn contains(&self, string: &str) -> bool {
-        let lowercase_path = self.path.to_lowercase();
-        let lowercase_string = string.to_lowercase();
+    /// Files and directories whose contents were not loaded are moved as a whole by refactors.
+    pub fn is_leaf(&self) -> bool {
         match &self.content {
-            PathContent::Directory(directory) => {
-                if lowercase_path.contains(&lowercase_string) {
-                    return true;
-                }
-                for info in directory.contents.values() {
-                    if info.contains(string) {
-                        return true;
-                    }
-                }
-                false
+            PathContent::File(_) => true,
+            PathContent::Directory(directory) => !directory.loaded
+        }
+    }
+
+}
+
+/// A displayed row of the workspace tree, once flattened according to collapsed directories
+/// and to the filter.
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub path: String,
+    pub depth: usize
+}
+
+/// Collects the paths that match a lowercase filter alongside the directories leading to them,
+/// returning whether the map had any match.
+pub fn filter_content(map: &BTreeMap<String, PathInfo>, filter: &str, matches: &mut HashSet<String>) -> bool {
+    let mut any = false;
+    for info in map.values() {
+        let mut matched = info.path.to_lowercase().contains(filter);
+        if let PathContent::Directory(directory) = &info.content {
+            matched |= filter_content(&directory.contents, filter, matches);
+        }
+        if matched {
+            matches.insert(info.path.clone());
+            any = true;
+        }
+    }
+    any
+}
+
+/// Flattens the tree-shaped map into the rows to display, skipping the contents of collapsed
+/// or unloaded directories and the paths that are not part of the filter matches.
+pub fn flatten_content(map: &BTreeMap<String, PathInfo>, collapsed: &HashSet<String>, matches: Option<&HashSet<String>>, depth: usize, rows: &mut Vec<TreeRow>) {
+    for info in map.values() {
+        if matches.is_some_and(|matches| !matches.contains(&info.path)) {
+            continue;
+        }
+        rows.push(TreeRow { path: info.path.clone(), depth });
+        if let PathContent::Directory(directory) = &info.content {
+            if directory.loaded && !collapsed.contains(&info.path) {
+                flatten_content(&directory.contents, collapsed, matches, depth + 1, rows);
             }
-            PathContent::File(_) => lowercase_path.contains(&lowercase_string)
         }
     }
 }
@@ -148,21 +222,22 @@ impl PathInfo {
 /// When setting the path information, you can provide content through a treemap representing
 /// provided directory contents that will be added to the current directory information at this
 /// location and if this location is not a directory then it will become one.
-fn fill_content(ref_sha: String, ref_path: String, ref_url: String, ref_name: String, provided_content: Option<BTreeMap<String, PathInfo>>, map: &mut BTreeMap<String, PathInfo>, remaining: &mut Vec<String>, i: usize, depth: usize) {
+fn fill_content(ref_sha: String, ref_path: String, ref_url: String, ref_name: String, ref_mode: EntryMode, provided_content: Option<BTreeMap<String, PathInfo>>, map: &mut BTreeMap<String, PathInfo>, remaining: &mut Vec<String>, i: usize, depth: usize) {
     if i == depth - 1 {
         let key = remaining.remove(0);
         let info = PathInfo {
             sha: ref_sha,
             path: ref_path,
             url: ref_url,
+            mode: if provided_content.is_some() { EntryMode::Directory } else { ref_mode },
             content: if map.contains_key(&key) {
                 let content = map.remove(&key).unwrap().content;
                 if provided_content.is_some() {
                     if let PathContent::Directory(mut directory) = content {
                         directory.contents.extend(provided_content.unwrap());
-                        PathContent::Directory(DirectoryInfo { name: ref_name, contents: directory.contents })
+                        PathContent::Directory(DirectoryInfo { name: ref_name, contents: directory.contents, loaded: directory.loaded })
                     } else {
-                        PathContent::Directory(DirectoryInfo { name: ref_name, contents: provided_content.unwrap() })
+                        PathContent::Directory(DirectoryInfo { name: ref_name, contents: provided_content.unwrap(), loaded: true })
                     }
                 }
                 else {
@@ -170,7 +245,7 @@ fn fill_content(ref_sha: String, ref_path: String, ref_url: String, ref_name: St
                 }
             } else {
                 if provided_content.is_some() {
-                    PathContent::Directory(DirectoryInfo { name: ref_name, contents: provided_content.unwrap() })
+                    PathContent::Directory(DirectoryInfo { name: ref_name, contents: provided_content.unwrap(), loaded: true })
                 }
                 else {
                     PathContent::File(FileInfo { name: ref_name })
@@ -181,32 +256,41 @@ fn fill_content(ref_sha: String, ref_path: String, ref_url: String, ref_name: St
     }
     else {
         let key = remaining.remove(0);
-        let mut previous: Option<PathInfo> = None;
+        // Only the information of the previous entry is kept aside, as cloning its whole subtree
+        // for every placed path gets far too slow on large repositories.
+        let mut previous: Option<(String, String, String)> = None;
+        let mut loaded = true;
         let mut inner: BTreeMap<String, PathInfo> = if map.contains_key(&key) {
             let value = map.remove(&key).unwrap();
-            previous = Some(value.clone());
+            previous = Some((value.sha, value.path, value.url));
             match value.content {
                 PathContent::File(_) => BTreeMap::new(),
-                PathContent::Directory(info) => info.contents
+                PathContent::Directory(info) => {
+                    loaded = info.loaded;
+                    info.contents
+                }
             }
         } else {
             BTreeMap::new()
         };
-        fill_content(ref_sha, ref_path, ref_url, ref_name, provided_content, &mut inner, remaining, i + 1, depth);
-        let directory_content = PathContent::Directory(DirectoryInfo { name: key.clone(), contents: inner });
+        let directory_path = ref_path.split("/").take(i + 1).collect::<Vec<&str>>().join("/");
+        fill_content(ref_sha, ref_path, ref_url, ref_name, ref_mode, provided_content, &mut inner, remaining, i + 1, depth);
+        let directory_content = PathContent::Directory(DirectoryInfo { name: key.clone(), contents: inner, loaded });
         let info = if previous.is_some() {
-            let previous_info = previous.unwrap();
+            let (sha, path, url) = previous.unwrap();
             PathInfo {
-                sha: previous_info.sha,
-                path: previous_info.path,
-                url: previous_info.url,
+                sha,
+                path,
+                url,
+                mode: EntryMode::Directory,
                 content: directory_content
             }
         } else {
             PathInfo {
                 sha: "".to_string(),
-                path: "".to_string(),
+                path: directory_path,
                 url: "".to_string(),
+                mode: EntryMode::Directory,
                 content: directory_content
             }
         };
@@ -220,12 +304,12 @@ fn propagate_path(content: PathContent, built_path: Vec<String>) -> (PathContent
             let mut map = BTreeMap::new();
             let mut refactors = HashMap::new();
             for (key, value) in directory.contents.into_iter() {
+                let is_file = value.is_leaf();
                 let previous_path = value.path;
                 let previous_sha = value.sha;
                 let child_path = [built_path.clone(), vec![key.clone()]].concat();
-                let is_file = matches!(&value.content, PathContent::File(_));
                 let (propagated, inner_refactors) = propagate_path(value.content, child_path.clone());
-                let refactored = PathInfo { sha: "".to_string(), path: child_path.join("/"), url: "".to_string(), content: propagated };
+                let refactored = PathInfo { sha: previous_sha.clone(), path: child_path.join("/"), url: "".to_string(), mode: value.mode, content: propagated };
                 let new_path = refactored.path.clone();
                 map.insert(key, refactored);
                 if is_file {
@@ -246,17 +330,52 @@ fn erase_content(path: String, map: &mut BTreeMap<String, PathInfo>, remaining:
     else {
         let key = remaining.remove(0);
         let value = map.remove(&key)?;
-        let previous = value.clone();
         let PathContent::Directory(mut directory) = value.content else { panic!("Should be a directory") };
         let removed = erase_content(path, &mut directory.contents, remaining, i + 1, depth, cleanup);
         if !directory.contents.is_empty() || !cleanup {
-            let directory_content = PathContent::Directory(DirectoryInfo { name: key.clone(), contents: directory.contents });
-            map.insert(key, PathInfo { sha: previous.sha, path: previous.path, url: previous.url, content: directory_content });
+            let directory_content = PathContent::Directory(DirectoryInfo { name: key.clone(), contents: directory.contents, loaded: directory.loaded });
+            map.insert(key, PathInfo { sha: value.sha, path: value.path, url: value.url, mode: EntryMode::Directory, content: directory_content });
         }
         removed
     }
 }
 
+/// Looks up the path information located at the provided path inside the tree-shaped map.
+pub fn find_content<'a>(map: &'a BTreeMap<String, PathInfo>, path: &str) -> Option<&'a PathInfo> {
+    let mut segments = path.split("/");
+    let mut info = map.get(segments.next()?)?;
+    for segment in segments {
+        let PathContent::Directory(directory) = &info.content else { return None };
+        info = directory.contents.get(segment)?;
+    }
+    Some(info)
+}
+
+fn find_content_mut<'a>(map: &'a mut BTreeMap<String, PathInfo>, path: &str) -> Option<&'a mut PathInfo> {
+    let mut segments = path.split("/");
+    let mut info = map.get_mut(segments.next()?)?;
+    for segment in segments {
+        let PathContent::Directory(directory) = &mut info.content else { return None };
+        info = directory.contents.get_mut(segment)?;
+    }
+    Some(info)
+}
+
+/// Places a single upstream entry inside the tree-shaped map. Directories are placed empty, and
+/// are marked as not loaded unless their contents are about to be placed too.
+fn place_entry(content: &mut BTreeMap<String, PathInfo>, path: &String, info: &UpstreamInfo, loaded: bool) {
+    let mut vec = path.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
+    let name = (&vec.last().unwrap()).to_string();
+    let len = &vec.len();
+    let provided_content = (info.mode == EntryMode::Directory).then(BTreeMap::new);
+    fill_content(info.sha.clone(), path.clone(), info.url.clone(), name, info.mode, provided_content, content, &mut vec, 0usize, len.clone());
+    if !loaded {
+        if let Some(PathInfo { content: PathContent::Directory(directory), .. }) = find_content_mut(content, path) {
+            directory.loaded = false;
+        }
+    }
+}
+
 pub (crate) fn debug_content(structure: &BTreeMap<String, PathInfo>, indentation: usize) {
     for (key, value) in structure {
         println!("{}{}: (", " ".repeat(indentation), key);
@@ -279,20 +398,53 @@ pub (crate) fn debug_content(structure: &BTreeMap<String, PathInfo>, indentation
 }
 
 pub async fn get_workspace_content(crab: Octocrab, info: WorkspaceInfo) -> (BTreeMap<String, PathInfo>, Modification, bool) {
-    let object = wrapper::get_repository_content(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id).await;
+    let mut object = wrapper::get_repository_content(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id).await;
+    // GitHub truncates recursive trees that are too large, in which case directories get loaded
+    // one level at a time when they are expanded.
+    let lazy = object.truncated;
+    if lazy {
+        object = wrapper::get_tree_level(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id).await;
+    }
     let mut structure: BTreeMap<String, PathInfo> = BTreeMap::new();
     let mut modification = Modification::new();
     for part in object.tree {
-        let mut vec = (&part.path).split("/").map(|s| s.to_string()).collect::<Vec<String>>();
-        let name = (&vec.last().unwrap()).to_string();
-        let len = &vec.len();
-        fill_content(part.sha, part.path.clone(), part.url, name, None, &mut structure, &mut vec, 0usize, len.clone());
-        modification.upstream.push(part.path);
+        let mode = EntryMode::from_git(&part.mode, &part.type_);
+        let upstream = UpstreamInfo { sha: part.sha, url: part.url, size: part.size, mode };
+        place_entry(&mut structure, &part.path, &upstream, !lazy);
+        if lazy && mode == EntryMode::Directory {
+            modification.unloaded.insert(part.path.clone());
+        }
+        modification.upstream.insert(part.path, upstream);
     }
     // debug_content(&structure, 0);
     (structure, modification, wrapper::is_private(&crab, &info.project.source_owner, &info.project.source_name).await)
 }
 
+pub async fn get_directory_content(crab: Octocrab, info: WorkspaceInfo, directory_sha: String) -> Vec<TreePart> {
+    wrapper::get_tree_level(&crab, &info.project.fork_owner, &info.project.fork_name, &directory_sha).await.tree
+}
+
+/// Places the entries of a lazily loaded directory inside the tree-shaped map and registers them
+/// as upstream. Entries with pending changes are left as they are displayed.
+pub fn load_workspace_directory(content: &mut BTreeMap<String, PathInfo>, modification: &mut Modification, path: String, parts: Vec<TreePart>) {
+    for part in parts {
+        let location = format!("{}/{}", path, part.path);
+        let mode = EntryMode::from_git(&part.mode, &part.type_);
+        let upstream = UpstreamInfo { sha: part.sha, url: part.url, size: part.size, mode };
+        if !modification.changes.contains_key(&location) {
+            place_entry(content, &location, &upstream, false);
+        }
+        if mode == EntryMode::Directory {
+            modification.unloaded.insert(location.clone());
+        }
+        modification.upstream.insert(location, upstream);
+    }
+    if let Some(PathInfo { content: PathContent::Directory(directory), .. }) = find_content_mut(content, &path) {
+        directory.loaded = true;
+    }
+    modification.unloaded.remove(&path);
+}
+
 pub async fn import_files(is_inside_directory: bool, import_location_path: String) -> HashMap<String, Vec<u8>> {
     let dialog = rfd::AsyncFileDialog::new();
     let files: Vec<rfd::FileHandle> = if is_inside_directory {
@@ -313,95 +465,487 @@ pub async fn import_files(is_inside_directory: bool, import_location_path: Strin
     map
 }
 
+/// Picks a local folder and reads all the files it contains, keyed by their location inside the
+/// workspace: the folder itself is placed inside the import location, keeping its structure.
+pub async fn import_folder(import_location_path: String) -> BTreeMap<String, Vec<u8>> {
+    match rfd::AsyncFileDialog::new().pick_folder().await {
+        Some(folder) => read_folder(folder.path().to_path_buf(), import_location_path),
+        None => BTreeMap::new()
+    }
+}
+
+/// Reads the files and folders dropped together from the file manager, keyed by their location
+/// inside the workspace like imported files and folders are.
+pub async fn import_dropped(dropped: Vec<PathBuf>, import_location_path: String) -> BTreeMap<String, Vec<u8>> {
+    let mut map = BTreeMap::new();
+    for path in dropped {
+        if path.is_dir() {
+            map.extend(read_folder(path, import_location_path.clone()));
+        }
+        else if let (Some(name), Ok(bytes)) = (path.file_name(), std::fs::read(&path)) {
+            let name = name.to_string_lossy().to_string();
+            let location = if !import_location_path.is_empty() { format!("{}/{}", import_location_path, name) } else { name };
+            map.insert(location, bytes);
+        }
+    }
+    map
+}
+
+fn read_folder(root: PathBuf, import_location_path: String) -> BTreeMap<String, Vec<u8>> {
+    let mut map = BTreeMap::new();
+    let base = root.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let mut remaining = vec![root.clone()];
+    while let Some(directory) = remaining.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else { continue };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else { continue };
+            let path = entry.path();
+            if file_type.is_dir() {
+                remaining.push(path);
+            }
+            else if file_type.is_file() {
+                let Ok(bytes) = std::fs::read(&path) else { continue };
+                let relative = path.strip_prefix(&root).unwrap().components()
+                    .map(|component| component.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<String>>()
+                    .join("/");
+                let location = [import_location_path.as_str(), base.as_str(), relative.as_str()].into_iter()
+                    .filter(|segment| !segment.is_empty())
+                    .collect::<Vec<&str>>()
+                    .join("/");
+                map.insert(location, bytes);
+            }
+        }
+    }
+    map
+}
+
 pub fn append_workspace_content(content: &mut BTreeMap<String, PathInfo>, paths: Vec<String>) {
     for path in paths {
         let mut vec = path.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
         let name = (&vec.last().unwrap()).to_string();
         let len = &vec.len();
-        fill_content("".to_string(), path, "".to_string(), name, None, content, &mut vec, 0usize, len.clone())
+        let mode = match find_content(content, &path).map(|info| info.mode) {
+            Some(EntryMode::Executable) => EntryMode::Executable,
+            _ => EntryMode::File
+        };
+        fill_content("".to_string(), path, "".to_string(), name, mode, None, content, &mut vec, 0usize, len.clone())
+    }
+}
+
+/// Resolves the location targeted by a refactor input, which is relative to the directory
+/// containing the origin location, failing when the input climbs above the root.
+pub fn resolve_refactor(origin_path: &str, refactor_input: &str) -> Result<String, String> {
+    let origin = origin_path.split("/").collect::<Vec<&str>>();
+    Path::new(&origin[..origin.len() - 1].join("/"))
+        .join(refactor_input)
+        .try_normalize()
+        .map(|path| path.to_str().unwrap().to_string().replace("\\", "/"))
+        .ok_or(format!("\"{}\" would leave the root of the repository.", refactor_input))
+}
+
+/// Git does not track empty directories, so created ones get an empty placeholder file, which is
+/// only sent while they are still empty.
+pub const FOLDER_PLACEHOLDER: &str = ".gitkeep";
+
+/// Longest path accepted, leaving room for the location of the clone within the 260 characters
+/// Windows allows without long path support.
+const MAX_PATH_LENGTH: usize = 200;
+
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9"
+];
+
+const INVALID_CHARACTERS: &str = "<>:\"\\|?*";
+
+/// Checks that a name can be used for a single segment of a path on every platform the
+/// repository may be cloned on.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Names cannot be empty.".to_string());
+    }
+    if name == "." || name == ".." {
+        return Err(format!("\"{}\" would leave the root of the repository.", name));
+    }
+    if name.eq_ignore_ascii_case(".git") {
+        return Err(format!("\"{}\" is reserved by git.", name));
+    }
+    if let Some(c) = name.chars().find(|c| INVALID_CHARACTERS.contains(*c) || c.is_control()) {
+        return Err(format!("\"{}\" contains {:?}, which is invalid on Windows.", name, c));
+    }
+    if name.ends_with(".") || name.ends_with(" ") {
+        return Err(format!("\"{}\" ends with a dot or a space, which Windows removes.", name));
+    }
+    let stem = name.split(".").next().unwrap().trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+        return Err(format!("\"{}\" is a reserved name on Windows.", name));
+    }
+    if name.len() > 255 {
+        return Err(format!("\"{}\" is longer than 255 bytes.", name));
+    }
+    Ok(())
+}
+
+/// Checks that a new path is valid and does not collide with the content of the workspace,
+/// either exactly, through a file that would have to become a directory, or only by case. The
+/// origin of a move is not considered as a collision, so that the case of a name can change.
+pub fn check_new_path(content: &BTreeMap<String, PathInfo>, path: &str, origin: Option<&str>) -> Result<(), String> {
+    if path.is_empty() || path.starts_with("/") {
+        return Err(format!("\"{}\" is outside of the repository.", path));
+    }
+    if path.chars().count() > MAX_PATH_LENGTH {
+        return Err(format!("{} is longer than {} characters.", path, MAX_PATH_LENGTH));
+    }
+    let segments = path.split("/").collect::<Vec<&str>>();
+    for segment in &segments {
+        check_name(segment)?;
+    }
+    let mut level = Some(content);
+    for (i, segment) in segments.iter().enumerate() {
+        let Some(map) = level else { break };
+        let partial = segments[..=i].join("/");
+        if origin == Some(partial.as_str()) {
+            break;
+        }
+        match map.get(*segment) {
+            Some(_) if i == segments.len() - 1 => return Err(format!("{} already exists.", partial)),
+            Some(info) => match &info.content {
+                PathContent::Directory(directory) => level = Some(&directory.contents),
+                _ => return Err(format!("{} is not a directory, it cannot contain {}.", partial, path))
+            },
+            None => {
+                let lowercase = segment.to_lowercase();
+                let parent = segments[..i].join("/");
+                let sibling = map.keys()
+                    .filter(|name| name.to_lowercase() == lowercase)
+                    .map(|name| if parent.is_empty() { name.to_string() } else { format!("{}/{}", parent, name) })
+                    .find(|sibling| origin != Some(sibling.as_str()));
+                if let Some(sibling) = sibling {
+                    return Err(format!("{} only differs from {} by case, which breaks clones on Windows and macOS.", partial, sibling));
+                }
+                level = None;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameMode {
+    Prefix,
+    Suffix,
+    Regex,
+    Numbering
+}
+
+impl RenameMode {
+    pub const ALL: [RenameMode; 4] = [RenameMode::Prefix, RenameMode::Suffix, RenameMode::Regex, RenameMode::Numbering];
+}
+
+impl std::fmt::Display for RenameMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RenameMode::Prefix => "Add Prefix",
+            RenameMode::Suffix => "Add Suffix",
+            RenameMode::Regex => "Replace (Regex)",
+            RenameMode::Numbering => "Numbering (#)"
+        })
+    }
+}
+
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind(".") {
+        Some(index) if index > 0 => (&name[..index], &name[index..]),
+        _ => (name, "")
+    }
+}
+
+/// Computes the new names of a batch rename, in the order of the provided names. Suffixes and
+/// numbering keep the extensions, and numbering replaces the first run of `#` in the pattern by
+/// the position of the name, padded to the length of that run.
+pub fn batch_rename(names: &[String], mode: RenameMode, pattern: &str, replacement: &str) -> Result<Vec<String>, String> {
+    let renamed = match mode {
+        RenameMode::Prefix => names.iter().map(|name| format!("{}{}", pattern, name)).collect::<Vec<String>>(),
+        RenameMode::Suffix => names.iter()
+            .map(|name| {
+                let (stem, extension) = split_extension(name);
+                format!("{}{}{}", stem, pattern, extension)
+            })
+            .collect(),
+        RenameMode::Regex => {
+            let regex = regex::Regex::new(pattern).map_err(|error| error.to_string())?;
+            names.iter().map(|name| regex.replace_all(name, replacement).to_string()).collect()
+        }
+        RenameMode::Numbering => {
+            let start = pattern.find("#").unwrap_or(pattern.len());
+            let width = pattern[start..].chars().take_while(|c| *c == '#').count();
+            names.iter().enumerate()
+                .map(|(i, name)| {
+                    let number = format!("{:0width$}", i + 1, width = width);
+                    let extension = split_extension(name).1;
+                    format!("{}{}{}{}", &pattern[..start], number, &pattern[start + width..], extension)
+                })
+                .collect()
+        }
+    };
+    for name in &renamed {
+        if name.contains("/") {
+            return Err(format!("\"{}\" is not a valid name.", name));
+        }
+        check_name(name)?;
     }
+    Ok(renamed)
 }
 
 /// Extracts path information from an origin location (and so removes it at this location) and if
 /// it is a directory it will also retain its content. Then it will append that path information to
-/// refactored location after propagating the new path through all inner items of the information.
-/// At the end, this function will return a map of all locations that were moved, linked to their
-/// new refactored locations and the sha that was originally inside the information at the origin.
-pub fn refactor_workspace_content(content: &mut BTreeMap<String, PathInfo>, origin_path: String, refactor_input: String, origin_sha: String) -> HashMap<String, (String, String)> {
+/// refactored location, relative to the workspace root such as the one resolved from a refactor
+/// input, after propagating the new path through all inner items of the information. At the end,
+/// this function will return a map of all locations that were moved, linked to their new
+/// refactored locations and the sha that was originally inside the information at the origin.
+pub fn relocate_workspace_content(content: &mut BTreeMap<String, PathInfo>, origin_path: String, normalized: String, origin_sha: String) -> HashMap<String, (String, String)> {
     let mut origin = origin_path.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
-    let normalized = Path::new(&origin.clone()[..origin.len().clone() - 1].join("/"))
-        .join(refactor_input)
-        .normalize()
-        .to_str()
-        .unwrap()
-        .to_string()
-        .replace("\\", "/");
     let mut refactor = normalized.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
     let origin_len = &origin.len();
     let to_propagate = erase_content(origin_path.clone(), content, &mut origin, 0usize, origin_len.clone(), false).unwrap();
-    let is_file = matches!(&to_propagate.content, PathContent::File(_));
-    let (propagation, mut trace) = propagate_path(to_propagate.content, refactor.clone());
+    let trace = place_propagated(content, to_propagate, origin_path, normalized, &mut refactor, origin_sha);
+    // debug_content(content, 0usize);
+    trace
+}
+
+/// Copies a path and everything it contains to a location relative to the workspace root, such
+/// as the one resolved from a duplicate input. Returns the copied locations like refactors do.
+pub fn duplicate_workspace_content(content: &mut BTreeMap<String, PathInfo>, origin_path: String, normalized: String) -> HashMap<String, (String, String)> {
+    let Some(to_propagate) = find_content(content, &origin_path).cloned() else { return HashMap::new() };
+    let mut duplicate = normalized.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
+    let origin_sha = to_propagate.sha.clone();
+    place_propagated(content, to_propagate, origin_path, normalized, &mut duplicate, origin_sha)
+}
+
+/// Places a path taken out of the tree-shaped map at another location, propagating the new
+/// location to everything it contains, and returns where each moved file comes from.
+fn place_propagated(content: &mut BTreeMap<String, PathInfo>, to_propagate: PathInfo, origin_path: String, normalized: String, destination: &mut Vec<String>, origin_sha: String) -> HashMap<String, (String, String)> {
+    let is_file = to_propagate.is_leaf();
+    let unloaded = is_file && to_propagate.mode == EntryMode::Directory;
+    let (propagated_sha, propagated_mode) = (to_propagate.sha.clone(), to_propagate.mode);
+    let (propagation, mut trace) = propagate_path(to_propagate.content, destination.clone());
     let erased = match propagation {
         PathContent::Directory(directory) => Some(directory.contents),
         PathContent::File(_) => None
     };
-    let refactor_len = &refactor.len();
-    fill_content("".to_string(), normalized.clone(), "".to_string(), refactor.last().unwrap().to_string(), erased, content, &mut refactor.clone(), 0usize, refactor_len.clone());
+    let destination_len = &destination.len();
+    fill_content(propagated_sha, normalized.clone(), "".to_string(), destination.last().unwrap().to_string(), propagated_mode, erased, content, destination, 0usize, destination_len.clone());
+    if unloaded {
+        if let Some(PathInfo { content: PathContent::Directory(directory), .. }) = find_content_mut(content, &normalized) {
+            directory.loaded = false;
+        }
+    }
     if is_file {
         trace.insert(origin_path, (normalized, origin_sha));
     }
-    // debug_content(content, 0usize);
     trace
 }
 
+/// Applies all pending changes of a modification to the tree-shaped map, used to display pending
+/// changes on top of a freshly fetched workspace content.
+pub fn replay_workspace_content(content: &mut BTreeMap<String, PathInfo>, modification: &Modification) {
+    for (path, change) in &modification.changes {
+        match change {
+            Change::AssignContent(_, mode) => {
+                let mut vec = path.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
+                let name = (&vec.last().unwrap()).to_string();
+                let len = &vec.len();
+                fill_content("".to_string(), path.clone(), "".to_string(), name, *mode, None, content, &mut vec, 0usize, len.clone());
+            }
+            Change::EraseContent => remove_workspace_content(content, path.clone())
+        }
+    }
+}
+
 pub fn remove_workspace_content(content: &mut BTreeMap<String, PathInfo>, path: String) {
     let mut vec = path.split("/").map(|s| s.to_string()).collect::<Vec<String>>();
     let len = &vec.len();
     erase_content(path, content, &mut vec, 0usize, len.clone(), true);
 }
 
-pub async fn get_file_content(crab: Octocrab, info: WorkspaceInfo, file_sha: String) -> Vec<u8> {
-    wrapper::get_decoded_blob(&crab, &info.project.fork_owner, &info.project.fork_name, &file_sha).await.unwrap()
+/// Restores the upstream state of a single path, both inside the modification and inside the
+/// tree-shaped map. If that path was renamed from an upstream location that got erased by the
+/// renaming, that origin location is restored too.
+pub fn revert_workspace_content(content: &mut BTreeMap<String, PathInfo>, modification: &mut Modification, path: String) {
+    let origin = modification.revert(&path);
+    for location in [Some(path), origin].into_iter().flatten() {
+        match modification.upstream_info(&location).cloned() {
+            Some(info) => {
+                if info.mode == EntryMode::Directory {
+                    remove_workspace_content(content, location.clone());
+                }
+                place_entry(content, &location, &info, !modification.unloaded.contains(&location));
+            }
+            None => remove_workspace_content(content, location)
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Fetches the contents of the provided .gitignore files, keyed by their path.
+pub async fn get_gitignores(crab: Octocrab, info: WorkspaceInfo, gitignores: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut contents = vec![];
+    for (path, sha) in gitignores {
+        if let Ok(bytes) = wrapper::get_decoded_blob(&crab, &info.project.fork_owner, &info.project.fork_name, &sha).await {
+            contents.push((path, String::from_utf8_lossy(&bytes).to_string()));
+        }
+    }
+    contents
+}
+
+pub async fn get_file_content(crab: Octocrab, info: WorkspaceInfo, file_sha: String) -> Result<Vec<u8>, String> {
+    wrapper::get_decoded_blob(&crab, &info.project.fork_owner, &info.project.fork_name, &file_sha).await
+}
+
+/// Fetches both sides of a pending change: the upstream blob it started from, and its pending
+/// content which may also be a blob when it was moved. Missing sides are left empty.
+pub async fn get_diff_contents(crab: Octocrab, info: WorkspaceInfo, upstream_sha: Option<String>, pending: Option<FileContent>) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let upstream = match upstream_sha {
+        Some(sha) => get_file_content(crab.clone(), info.clone(), sha).await?,
+        None => vec![]
+    };
+    let pending = match pending {
+        Some(FileContent::Bytes(bytes)) => bytes,
+        Some(FileContent::Sha(sha)) => get_file_content(crab, info, sha).await?,
+        None => vec![]
+    };
+    Ok((upstream, pending))
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum FileContent {
     Bytes(Vec<u8>),
     Sha(String)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Change {
-    AssignContent(FileContent),
+    AssignContent(FileContent, EntryMode),
     EraseContent
 }
 
+#[derive(Debug, Clone)]
+pub struct UpstreamInfo {
+    pub sha: String,
+    pub url: String,
+    pub size: Option<u64>,
+    pub mode: EntryMode
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Replaced,
+    Renamed,
+    Deleted
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub origin: Option<String>,
+    pub size: Option<u64>
+}
+
 #[derive(Debug, Clone)]
 pub struct Modification {
-    upstream: Vec<String>,
-    changes: HashMap<String, Change>
+    upstream: BTreeMap<String, UpstreamInfo>,
+    unloaded: HashSet<String>,
+    changes: HashMap<String, Change>,
+    renames: HashMap<String, String>,
+    journal: Option<Journal>
+}
+
+#[derive(Debug, Clone)]
+struct Journal {
+    changes: HashMap<String, Option<Change>>,
+    renames: HashMap<String, String>
+}
+
+/// Changes brought to a modification while it was recording, each path being linked to the change
+/// it had before the recording and the change it has after it.
+#[derive(Debug, Clone, Default)]
+pub struct ModificationDelta {
+    changes: HashMap<String, (Option<Change>, Option<Change>)>,
+    renames: (HashMap<String, String>, HashMap<String, String>)
 }
 
 impl Modification {
-    pub fn new() -> Modification { Modification { upstream: Vec::new(), changes: HashMap::new() } }
+    pub fn new() -> Modification { Modification { upstream: BTreeMap::new(), unloaded: HashSet::new(), changes: HashMap::new(), renames: HashMap::new(), journal: None } }
+
+    /// Writes a change for a path, keeping track of the previous one if the modification is recording.
+    fn write(&mut self, path: String, change: Option<Change>) {
+        if let Some(journal) = &mut self.journal {
+            if !journal.changes.contains_key(&path) {
+                journal.changes.insert(path.clone(), self.changes.get(&path).cloned());
+            }
+        }
+        match change {
+            Some(change) => { self.changes.insert(path, change); }
+            None => { self.changes.remove(&path); }
+        }
+    }
 
     pub fn view(&self, path: &String) -> Option<&FileContent> {
-        if let Change::AssignContent(content) = self.changes.get(path)? {
+        if let Change::AssignContent(content, _) = self.changes.get(path)? {
             Some(content)
         } else {
             None
         }
     }
 
+    /// Assigns content to a path, keeping the executable mode of the upstream file it replaces.
+    /// Assigns the content of a regular file to a path. An executable stays executable, while a
+    /// symbolic link becomes a regular file, as the content is no longer the path it points to.
     pub fn set(&mut self, path: String, content: FileContent) {
-        self.changes.insert(path, Change::AssignContent(content));
+        let mode = match self.mode(&path).or(self.upstream.get(&path).map(|info| info.mode)) {
+            Some(EntryMode::Executable) => EntryMode::Executable,
+            _ => EntryMode::File
+        };
+        self.set_with_mode(path, content, mode);
+    }
+
+    pub fn set_with_mode(&mut self, path: String, content: FileContent, mode: EntryMode) {
+        let unchanged = match (&content, self.upstream.get(&path)) {
+            (FileContent::Sha(sha), Some(info)) => &info.sha == sha && info.mode == mode,
+            _ => false
+        };
+        if unchanged {
+            self.write(path, None);
+        }
+        else {
+            self.write(path, Some(Change::AssignContent(content, mode)));
+        }
+    }
+
+    /// The mode a path currently has, either from its pending content or from upstream.
+    pub fn mode(&self, path: &String) -> Option<EntryMode> {
+        match self.changes.get(path) {
+            Some(Change::AssignContent(_, mode)) => Some(*mode),
+            Some(Change::EraseContent) => None,
+            None => self.upstream.get(path).map(|info| info.mode)
+        }
+    }
+
+    pub fn is_symlink(&self, path: &String) -> bool {
+        self.mode(path) == Some(EntryMode::Symlink)
     }
 
     pub fn refactor(&mut self, origin: String, refactor: String, origin_sha: String) {
         if origin != refactor {
+            let root = self.renames.remove(&origin).or(self.upstream.contains_key(&origin).then(|| origin.clone()));
+            let mode = self.mode(&origin).unwrap_or(EntryMode::File);
             let content = if self.changes.contains_key(&origin) {
-                let change = self.changes.remove(&origin).unwrap();
-                if let Change::AssignContent(local_content) = change {
+                let change = self.changes.get(&origin).cloned().unwrap();
+                self.write(origin.clone(), None);
+                if let Change::AssignContent(local_content, _) = change {
                     Some(local_content)
                 } else {
                     None
@@ -411,50 +955,497 @@ impl Modification {
             };
             if content.is_some() {
                 self.erase(origin);
-                self.set(refactor, content.unwrap());
+                self.set_with_mode(refactor.clone(), content.unwrap(), mode);
+                if let Some(root) = root.filter(|root| root != &refactor) {
+                    self.renames.insert(refactor, root);
+                }
             }
         }
     }
 
+    /// Copies the content of a path to another one, reusing its blob or its pending content so that
+    /// nothing gets uploaded twice.
+    pub fn copy(&mut self, origin: &String, destination: String, origin_sha: String) {
+        let mode = self.mode(origin).unwrap_or(EntryMode::File);
+        let content = match self.changes.get(origin) {
+            Some(Change::AssignContent(content, _)) => content.clone(),
+            _ => match self.upstream.get(origin) {
+                Some(info) if origin_sha.is_empty() => FileContent::Sha(info.sha.clone()),
+                _ => FileContent::Sha(origin_sha)
+            }
+        };
+        self.set_with_mode(destination, content, mode);
+    }
+
+    /// Erases a path and everything it contains: upstream files get marked as erased, while
+    /// pending content that does not exist upstream is simply dropped. Directories that were
+    /// not loaded are erased as a whole since their contents are unknown.
     pub fn erase(&mut self, path: String) {
-        if self.upstream.contains(&path) {
-            self.changes.insert(path, Change::EraseContent);
+        let prefix = format!("{}/", path);
+        let contained = |location: &String| location == &path || location.starts_with(&prefix);
+        let upstream = self.upstream.range(path.clone()..)
+            .take_while(|(location, _)| location.starts_with(&path))
+            .filter(|(location, info)| contained(location) && (info.mode != EntryMode::Directory || self.unloaded.contains(*location)))
+            .map(|(location, _)| location.clone())
+            .collect::<Vec<String>>();
+        let pending = self.changes.keys()
+            .filter(|location| contained(location) && !self.upstream.contains_key(*location))
+            .cloned()
+            .collect::<Vec<String>>();
+        for location in pending {
+            self.renames.remove(&location);
+            self.write(location, None);
         }
-        else if self.changes.contains_key(&path) {
-            self.changes.remove(&path);
+        for location in upstream {
+            self.renames.remove(&location);
+            self.write(location, Some(Change::EraseContent));
+        }
+    }
+
+    /// Drops the change of a single path, returning the origin location it was renamed from if
+    /// that origin was erased by the renaming and so got restored as well.
+    pub fn revert(&mut self, path: &String) -> Option<String> {
+        let origin = self.renames.remove(path)
+            .filter(|origin| matches!(self.changes.get(origin), Some(Change::EraseContent)));
+        self.write(path.clone(), None);
+        if let Some(origin) = &origin {
+            self.write(origin.clone(), None);
         }
+        origin
+    }
+
+    pub fn origin(&self, path: &String) -> Option<&String> {
+        self.renames.get(path)
+    }
+
+    /// The upstream .gitignore files, alongside their sha.
+    pub fn gitignores(&self) -> Vec<(String, String)> {
+        self.upstream.iter()
+            .filter(|(path, info)| path.rsplit("/").next() == Some(".gitignore") && info.mode != EntryMode::Directory)
+            .map(|(path, info)| (path.clone(), info.sha.clone()))
+            .collect()
+    }
+
+    /// Checks if the contents of a directory can be fetched from upstream, which is not the case
+    /// of directories that were moved or replaced.
+    pub fn can_load(&self, path: &String) -> bool {
+        !self.changes.contains_key(path) && self.upstream.get(path).is_some_and(|info| info.mode == EntryMode::Directory)
+    }
+
+    /// Checks if a path exists upstream, or may exist upstream as it is located inside a
+    /// directory that was not loaded yet.
+    fn may_be_upstream(&self, path: &String) -> bool {
+        self.upstream.contains_key(path) || path.match_indices("/").any(|(index, _)| self.unloaded.contains(&path[..index]))
+    }
+
+    pub fn upstream_info(&self, path: &String) -> Option<&UpstreamInfo> {
+        self.upstream.get(path)
+    }
+
+    pub fn status(&self, path: &String) -> Option<ChangeKind> {
+        match self.changes.get(path)? {
+            Change::AssignContent(..) if self.renames.contains_key(path) => Some(ChangeKind::Renamed),
+            Change::AssignContent(..) if self.upstream.contains_key(path) => Some(ChangeKind::Replaced),
+            Change::AssignContent(..) => Some(ChangeKind::Added),
+            Change::EraseContent => Some(ChangeKind::Deleted)
+        }
+    }
+
+    /// Checks if there are pending changes at this path or anywhere inside it.
+    pub fn touches(&self, path: &String) -> bool {
+        let prefix = format!("{}/", path);
+        self.changes.keys().any(|location| location == path || location.starts_with(&prefix))
+    }
+
+    /// Lists all pending changes sorted by path. Erasures that only come from a renaming are not
+    /// listed on their own since they are part of the renamed entry.
+    pub fn pending(&self) -> Vec<PendingChange> {
+        let origins = self.renames.values().collect::<Vec<&String>>();
+        let mut pending = self.changes.iter()
+            .filter(|(path, change)| !(matches!(change, Change::EraseContent) && origins.contains(path)))
+            .map(|(path, change)| {
+                let origin = self.renames.get(path).cloned();
+                let size = match change {
+                    Change::AssignContent(FileContent::Bytes(bytes), _) => Some(bytes.len() as u64),
+                    Change::AssignContent(FileContent::Sha(_), _) => self.upstream.get(origin.as_ref().unwrap_or(path)).and_then(|info| info.size),
+                    Change::EraseContent => self.upstream.get(path).and_then(|info| info.size)
+                };
+                PendingChange { path: path.clone(), kind: self.status(path).unwrap(), origin, size }
+            })
+            .collect::<Vec<PendingChange>>();
+        pending.sort_by(|a, b| a.path.cmp(&b.path));
+        pending
     }
 
     pub fn reset(&mut self) {
         self.changes.clear();
+        self.renames.clear();
+        self.journal = None;
+    }
+
+    /// Takes the changes of the provided paths out of this modification into a new one sharing the
+    /// same upstream. Erasures of renamed origins are taken along with their renamed entries.
+    pub fn extract(&mut self, paths: &[String]) -> Modification {
+        let mut extracted = Modification { upstream: self.upstream.clone(), unloaded: self.unloaded.clone(), changes: HashMap::new(), renames: HashMap::new(), journal: None };
+        for path in paths {
+            if let Some(origin) = self.renames.remove(path) {
+                if matches!(self.changes.get(&origin), Some(Change::EraseContent)) {
+                    extracted.changes.insert(origin.clone(), self.changes.remove(&origin).unwrap());
+                }
+                extracted.renames.insert(path.clone(), origin);
+            }
+            if let Some(change) = self.changes.remove(path) {
+                extracted.changes.insert(path.clone(), change);
+            }
+        }
+        extracted
+    }
+
+    /// Forgets the changes that were sent, keeping the ones brought to the same paths while they
+    /// were being sent.
+    pub fn settle(&mut self, sent: &Modification) {
+        for (path, change) in &sent.changes {
+            if self.changes.get(path) == Some(change) {
+                self.changes.remove(path);
+            }
+        }
+        for (path, origin) in &sent.renames {
+            if self.renames.get(path) == Some(origin) {
+                self.renames.remove(path);
+            }
+        }
+    }
+
+    /// Moves pending changes on top of this modification, which usually comes with a freshly
+    /// fetched upstream. Erasures and renamings that do not apply anymore upstream are dropped.
+    pub fn rebase(&mut self, pending: Modification) {
+        for (path, change) in pending.changes {
+            match change {
+                Change::AssignContent(content, mode) => self.set_with_mode(path, content, mode),
+                Change::EraseContent => if self.may_be_upstream(&path) {
+                    self.write(path, Some(Change::EraseContent));
+                }
+            }
+        }
+        self.renames = pending.renames.into_iter()
+            .filter(|(path, origin)| self.upstream.contains_key(origin) && self.changes.contains_key(path))
+            .collect();
+    }
+
+    /// Starts keeping track of the changes brought to this modification until the delta is taken.
+    pub fn record(&mut self) {
+        self.journal = Some(Journal { changes: HashMap::new(), renames: self.renames.clone() });
+    }
+
+    /// Stops the recording and returns the changes brought to this modification since it started.
+    pub fn delta(&mut self) -> ModificationDelta {
+        let Some(journal) = self.journal.take() else { return ModificationDelta::default() };
+        ModificationDelta {
+            changes: journal.changes.into_iter()
+                .map(|(path, before)| {
+                    let after = self.changes.get(&path).cloned();
+                    (path, (before, after))
+                })
+                .collect(),
+            renames: (journal.renames, self.renames.clone())
+        }
+    }
+
+    pub fn rewind(&mut self, delta: &ModificationDelta) {
+        for (path, (before, _)) in &delta.changes {
+            self.write(path.clone(), before.clone());
+        }
+        self.renames = delta.renames.0.clone();
+    }
+
+    pub fn replay(&mut self, delta: &ModificationDelta) {
+        for (path, (_, after)) in &delta.changes {
+            self.write(path.clone(), after.clone());
+        }
+        self.renames = delta.renames.1.clone();
     }
 
     pub fn present(&self) -> bool {
         !self.changes.is_empty()
     }
+
+    /// Drops the placeholders of created folders that got other entries since, as git only needs
+    /// them to keep empty folders.
+    fn drop_placeholders(&mut self) {
+        let suffix = format!("/{}", FOLDER_PLACEHOLDER);
+        let redundant = self.changes.iter()
+            .filter(|(path, change)| {
+                !self.upstream.contains_key(*path) && matches!(change, Change::AssignContent(FileContent::Bytes(bytes), _) if bytes.is_empty())
+            })
+            .filter_map(|(path, _)| path.strip_suffix(&suffix).map(|directory| (path, format!("{}/", directory))))
+            .filter(|(placeholder, directory)| {
+                let assigned = self.changes.iter()
+                    .any(|(path, change)| path != *placeholder && path.starts_with(directory) && matches!(change, Change::AssignContent(..)));
+                let kept = self.upstream.iter()
+                    .any(|(path, info)| path.starts_with(directory) && info.mode != EntryMode::Directory && !self.changes.contains_key(path));
+                assigned || kept
+            })
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<String>>();
+        for path in redundant {
+            self.changes.remove(&path);
+        }
+    }
 }
 
-pub async fn send_contents(crab: Octocrab, info: WorkspaceInfo, modification: Modification, modification_name: String) {
+#[derive(Debug, Clone)]
+pub enum SendProgress {
+    /// The amount of completed requests, out of the total amount of requests of the sending.
+    Advanced(usize, usize),
+    Sent,
+    Failed(String)
+}
+
+/// Sends the changes as a commit, reporting progress after each blob upload and after the
+/// creation of the tree, of the commit and of the branch update. A failed request ends the
+/// sending with its error, without anything being committed.
+pub fn send_contents(crab: Octocrab, info: WorkspaceInfo, modification: Modification, commit_message: String, author: Option<CommitIdentity>) -> impl Stream<Item = SendProgress> {
+    iced::stream::channel(16, move |mut sender| async move {
+        let progress = match send_commit(&crab, &info, modification, &commit_message, author.as_ref(), &mut sender).await {
+            Ok(()) => SendProgress::Sent,
+            Err(error) => SendProgress::Failed(error)
+        };
+        let _ = sender.send(progress).await;
+    })
+}
+
+async fn send_commit(crab: &Octocrab, info: &WorkspaceInfo, mut modification: Modification, commit_message: &str, author: Option<&CommitIdentity>, sender: &mut Sender<SendProgress>) -> Result<(), String> {
+    let (owner, name) = (&info.project.fork_owner, &info.project.fork_name);
+    modification.drop_placeholders();
+    let uploads = modification.changes.values().filter(|change| matches!(change, Change::AssignContent(FileContent::Bytes(_), _))).count();
+    let total = uploads + 3;
+    let mut completed = 0;
     let mut tree_parts = vec![];
     for (path, change) in modification.changes {
         match change {
-            Change::AssignContent(content) => {
+            Change::AssignContent(content, mode) => {
+                let (mode, type_) = (mode.mode().to_string(), mode.object_type().to_string());
                 match content {
                     FileContent::Sha(sha) => {
-                        tree_parts.push(TreeCreationPart { path, mode: "100644".to_string(), type_: "blob".to_string(), sha: Some(sha) });
+                        tree_parts.push(TreeCreationPart { path, mode, type_, sha: Some(sha) });
                     }
                     FileContent::Bytes(bytes) => {
-                        let blob = wrapper::create_blob(&crab, &info.project.fork_owner, &info.project.fork_name, bytes).await;
-                        tree_parts.push(TreeCreationPart { path, mode: "100644".to_string(), type_: "blob".to_string(), sha: Some(blob.sha) });
+                        let blob = wrapper::create_blob(crab, owner, name, bytes).await.map_err(|error| format!("{} could not be uploaded: {}", path, error))?;
+                        tree_parts.push(TreeCreationPart { path, mode, type_, sha: Some(blob.sha) });
+                        completed += 1;
+                        let _ = sender.send(SendProgress::Advanced(completed, total)).await;
                     }
                 };
             }
             Change::EraseContent => {
-                tree_parts.push(TreeCreationPart { path, mode: "100644".to_string(), type_: "blob".to_string(), sha: None });
+                let mode = modification.upstream.get(&path).map(|info| info.mode).unwrap_or(EntryMode::File);
+                tree_parts.push(TreeCreationPart { path, mode: mode.mode().to_string(), type_: mode.object_type().to_string(), sha: None });
             }
         }
     }
-    let (parent_sha, tree) = wrapper::create_tree(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id, tree_parts).await.unwrap();
-    let commit_sha = wrapper::create_commit(&crab, &info.project.fork_owner, &info.project.fork_name, &modification_name, &parent_sha, &tree.sha).await;
-    wrapper::push_commit(&crab, &info.project.fork_owner, &info.project.fork_name, &info.workspace_id, &parent_sha, &commit_sha).await;
+    let (parent_sha, tree) = wrapper::create_tree(crab, owner, name, &info.workspace_id, tree_parts).await
+        .map_err(|error| format!("The tree could not be created: {}", error))?
+        .ok_or("The workspace branch does not point to any commit.")?;
+    let _ = sender.send(SendProgress::Advanced(uploads + 1, total)).await;
+    let commit_sha = wrapper::create_commit(crab, owner, name, commit_message, &parent_sha, &tree.sha, author).await
+        .map_err(|error| format!("The commit could not be created: {}", error))?;
+    let _ = sender.send(SendProgress::Advanced(uploads + 2, total)).await;
+    wrapper::push_commit(crab, owner, name, &info.workspace_id, &parent_sha, &commit_sha).await
+        .map_err(|error| format!("The workspace branch could not be updated: {}", error))?;
+    let _ = sender.send(SendProgress::Advanced(total, total)).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn content(paths: &[&str]) -> BTreeMap<String, PathInfo> {
+        let mut content = BTreeMap::new();
+        append_workspace_content(&mut content, names(paths));
+        content
+    }
+
+    #[test]
+    fn refactors_resolve_relative_to_the_origin_directory() {
+        assert_eq!(resolve_refactor("a/b/stone.png", "dirt.png"), Ok("a/b/dirt.png".to_string()));
+        assert_eq!(resolve_refactor("a/b/stone.png", "../dirt.png"), Ok("a/dirt.png".to_string()));
+        assert_eq!(resolve_refactor("a/b/stone.png", "./c/../dirt.png"), Ok("a/b/dirt.png".to_string()));
+        assert_eq!(resolve_refactor("stone.png", "c/dirt.png"), Ok("c/dirt.png".to_string()));
+    }
+
+    #[test]
+    fn refactors_cannot_leave_the_root() {
+        assert!(resolve_refactor("stone.png", "../x").is_err());
+        assert!(resolve_refactor("stone.png", "../../x").is_err());
+        assert!(resolve_refactor("a/stone.png", "../../b").is_err());
+        assert!(resolve_refactor("stone.png", "a/../../b").is_err());
+    }
+
+    #[test]
+    fn names_invalid_on_any_platform_are_rejected() {
+        assert!(check_name("stone.png").is_ok());
+        assert!(check_name(".gitignore").is_ok());
+        assert!(check_name("console.png").is_ok());
+        assert!(check_name("").is_err());
+        assert!(check_name(".").is_err());
+        assert!(check_name("..").is_err());
+        assert!(check_name(".GIT").is_err());
+        assert!(check_name("a:b").is_err());
+        assert!(check_name("a?").is_err());
+        assert!(check_name("a\tb").is_err());
+        assert!(check_name("stone.").is_err());
+        assert!(check_name("stone ").is_err());
+        assert!(check_name(&"a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn reserved_windows_names_are_rejected_with_any_extension_or_case() {
+        assert!(check_name("CON").is_err());
+        assert!(check_name("con.png").is_err());
+        assert!(check_name("Lpt1.txt.bak").is_err());
+        assert!(check_name("nul .png").is_err());
+        assert!(check_name("COM10").is_ok());
+    }
+
+    #[test]
+    fn new_paths_cannot_leave_the_root() {
+        let content = content(&["a/stone.png"]);
+        assert!(check_new_path(&content, "", None).is_err());
+        assert!(check_new_path(&content, "/a/dirt.png", None).is_err());
+        assert!(check_new_path(&content, "a/../dirt.png", None).is_err());
+        assert!(check_new_path(&content, &"a/".repeat(101), None).is_err());
+    }
+
+    #[test]
+    fn new_paths_cannot_collide_exactly() {
+        let content = content(&["a/stone.png"]);
+        assert!(check_new_path(&content, "a/dirt.png", None).is_ok());
+        assert!(check_new_path(&content, "b/stone.png", None).is_ok());
+        assert!(check_new_path(&content, "a/stone.png", None).is_err());
+        assert!(check_new_path(&content, "a", None).is_err());
+    }
+
+    #[test]
+    fn new_paths_cannot_collide_by_case() {
+        let content = content(&["a/stone.png"]);
+        assert!(check_new_path(&content, "a/Stone.png", None).is_err());
+        assert!(check_new_path(&content, "A/dirt.png", None).is_err());
+    }
+
+    #[test]
+    fn files_cannot_become_directories() {
+        let content = content(&["a/stone.png"]);
+        assert!(check_new_path(&content, "a/stone.png/dirt.png", None).is_err());
+    }
+
+    #[test]
+    fn the_origin_of_a_move_is_not_a_collision() {
+        let content = content(&["a/stone.png", "a/dirt.png"]);
+        assert!(check_new_path(&content, "a/Stone.png", Some("a/stone.png")).is_ok());
+        assert!(check_new_path(&content, "A/stone.png", Some("a")).is_ok());
+        assert!(check_new_path(&content, "a/Dirt.png", Some("a/stone.png")).is_err());
+        assert!(check_new_path(&content, "a/dirt.png", Some("a/stone.png")).is_err());
+    }
+
+    #[test]
+    fn prefixes_are_prepended_to_whole_names() {
+        let renamed = batch_rename(&names(&["stone.png", "dirt.png.mcmeta", ".hidden"]), RenameMode::Prefix, "old_", "");
+        assert_eq!(renamed, Ok(names(&["old_stone.png", "old_dirt.png.mcmeta", "old_.hidden"])));
+    }
+
+    #[test]
+    fn suffixes_are_inserted_before_the_last_extension() {
+        let renamed = batch_rename(&names(&["stone.png", "dirt.png.mcmeta", "README", ".hidden"]), RenameMode::Suffix, "_old", "");
+        assert_eq!(renamed, Ok(names(&["stone_old.png", "dirt.png_old.mcmeta", "README_old", ".hidden_old"])));
+    }
+
+    #[test]
+    fn regex_replaces_every_match_with_captures() {
+        let renamed = batch_rename(&names(&["stone_1.png", "stone_2_top.png"]), RenameMode::Regex, r"_(\d+)", "-$1");
+        assert_eq!(renamed, Ok(names(&["stone-1.png", "stone-2_top.png"])));
+        let renamed = batch_rename(&names(&["a_b_c.png"]), RenameMode::Regex, "_", "");
+        assert_eq!(renamed, Ok(names(&["abc.png"])));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        assert!(batch_rename(&names(&["stone.png"]), RenameMode::Regex, "(", "").is_err());
+    }
+
+    #[test]
+    fn numbering_pads_to_the_first_run_of_hashes_and_keeps_extensions() {
+        let renamed = batch_rename(&names(&["b.png", "a.ogg", "c"]), RenameMode::Numbering, "frame_###", "");
+        assert_eq!(renamed, Ok(names(&["frame_001.png", "frame_002.ogg", "frame_003"])));
+        let renamed = batch_rename(&names(&["a.png", "b.png"]), RenameMode::Numbering, "#_v#", "");
+        assert_eq!(renamed, Ok(names(&["1_v#.png", "2_v#.png"])));
+    }
+
+    #[test]
+    fn numbering_without_hashes_appends_the_position() {
+        let renamed = batch_rename(&names(&["a.png", "b.png"]), RenameMode::Numbering, "tile", "");
+        assert_eq!(renamed, Ok(names(&["tile1.png", "tile2.png"])));
+    }
+
+    #[test]
+    fn numbers_grow_past_the_padding() {
+        let list = (0..10).map(|i| format!("{}.png", i)).collect::<Vec<String>>();
+        let renamed = batch_rename(&list, RenameMode::Numbering, "#", "").unwrap();
+        assert_eq!(renamed[8], "9.png");
+        assert_eq!(renamed[9], "10.png");
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        assert!(batch_rename(&names(&["a.png"]), RenameMode::Prefix, "dir/", "").is_err());
+        assert!(batch_rename(&names(&["a.png"]), RenameMode::Regex, ".*", "").is_err());
+        assert!(batch_rename(&names(&["a.png"]), RenameMode::Regex, r"\.png", ".").is_err());
+        assert!(batch_rename(&names(&["a.png"]), RenameMode::Suffix, ":", "").is_err());
+        assert!(batch_rename(&names(&["a.png"]), RenameMode::Regex, "a", "CON").is_err());
+        assert!(batch_rename(&names(&["a.png", "b.png"]), RenameMode::Regex, r"^b\.png$", "..").is_err());
+    }
+
+    fn upstream(entries: &[(&str, EntryMode)]) -> Modification {
+        let mut modification = Modification::new();
+        for (path, mode) in entries {
+            modification.upstream.insert(path.to_string(), UpstreamInfo { sha: format!("{}-sha", path), url: String::new(), size: None, mode: *mode });
+        }
+        modification
+    }
+
+    #[test]
+    fn regular_files_replace_symbolic_links() {
+        let mut modification = upstream(&[("link", EntryMode::Symlink)]);
+        assert!(modification.is_symlink(&"link".to_string()));
+        modification.set("link".to_string(), FileContent::Bytes(b"content".to_vec()));
+        assert_eq!(modification.mode(&"link".to_string()), Some(EntryMode::File));
+        assert!(!modification.is_symlink(&"link".to_string()));
+    }
+
+    #[test]
+    fn executables_stay_executable_when_replaced() {
+        let mut modification = upstream(&[("run.sh", EntryMode::Executable)]);
+        modification.set("run.sh".to_string(), FileContent::Bytes(b"echo".to_vec()));
+        assert_eq!(modification.mode(&"run.sh".to_string()), Some(EntryMode::Executable));
+        modification.erase("run.sh".to_string());
+        modification.set("run.sh".to_string(), FileContent::Bytes(b"echo".to_vec()));
+        assert_eq!(modification.mode(&"run.sh".to_string()), Some(EntryMode::Executable));
+    }
+
+    #[test]
+    fn renamed_entries_keep_their_mode_until_replaced() {
+        let mut modification = upstream(&[("run.sh", EntryMode::Executable), ("link", EntryMode::Symlink)]);
+        modification.refactor("run.sh".to_string(), "start.sh".to_string(), "run.sh-sha".to_string());
+        modification.refactor("link".to_string(), "alias".to_string(), "link-sha".to_string());
+        assert_eq!(modification.mode(&"start.sh".to_string()), Some(EntryMode::Executable));
+        assert!(modification.is_symlink(&"alias".to_string()));
+        modification.set("start.sh".to_string(), FileContent::Bytes(b"echo".to_vec()));
+        modification.set("alias".to_string(), FileContent::Bytes(b"content".to_vec()));
+        assert_eq!(modification.mode(&"start.sh".to_string()), Some(EntryMode::Executable));
+        assert_eq!(modification.mode(&"alias".to_string()), Some(EntryMode::File));
+    }
 }
@@ -1,10 +1,10 @@
-use base64::{DecodeError, Engine};
+use base64::Engine;
 use either::Either;
 use http::header::ACCEPT;
 use iced::widget::image;
 use octocrab::auth::{Continue, DeviceCodes, OAuth};
 use octocrab::models::pulls::PullRequest;
-use octocrab::models::repos::{Branch, CommitObject, Object};
+use octocrab::models::repos::{Branch, Object};
 use octocrab::models::Repository;
 use octocrab::params::repos::Reference;
 use octocrab::params::State;
@@ -68,6 +68,40 @@ pub fn set_stored_token(oauth: OAuth) -> octocrab::Result<OAuth> {
     Ok(oauth)
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub author_name: String,
+    pub author_email: String,
+    pub record_version: bool,
+    pub custom_trailer: String,
+    pub ignore_patterns: String
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            author_name: "".to_string(),
+            author_email: "".to_string(),
+            record_version: true,
+            custom_trailer: "".to_string(),
+            ignore_patterns: "".to_string()
+        }
+    }
+}
+
+pub fn get_stored_settings() -> Option<Settings> {
+    let username = whoami::username();
+    let entry = keyring::Entry::new("illusionna-settings-storage", &username).ok()?;
+    serde_json::from_slice(&entry.get_secret().ok()?).ok()
+}
+
+pub fn set_stored_settings(settings: &Settings) {
+    let username = whoami::username();
+    let entry = keyring::Entry::new("illusionna-settings-storage", &username).unwrap();
+    entry.set_secret(&serde_json::to_vec(settings).unwrap()).unwrap();
+}
+
 pub async fn embedded_oauth_process() -> octocrab::Result<Octocrab> {
     let oauth: OAuth = match get_stored_token() {
         Some(oauth) => oauth,
@@ -124,12 +158,50 @@ pub struct AccountInfo {
     pub name: String,
     pub avatar: image::Handle,
     pub count: usize,
-    pub profile: Url
+    pub profile: Url,
+    pub identity: CommitIdentity
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserProfile {
+    pub login: String,
+    pub id: u64,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Url,
+    pub html_url: Url
+}
+
+/// Name and email recorded as the author of a git commit.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitIdentity {
+    pub name: String,
+    pub email: String
+}
+
+/// The email GitHub associates to an account when it keeps its actual email private.
+pub fn noreply_email(id: u64, login: &str) -> String {
+    format!("{}+{}@users.noreply.github.com", id, login)
+}
+
+/// Whether an email has a single @ between a local part and a dotted domain, without whitespace
+/// or angle brackets, which would break the author line of the commit.
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once("@") else { return false };
+    !local.is_empty()
+        && !domain.contains("@")
+        && domain.contains(".")
+        && domain.split(".").all(|part| !part.is_empty())
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control() || c == '<' || c == '>')
 }
 
 pub async fn get_account_info(crab: Octocrab, count: usize) -> AccountInfo {
-    let author = crab.current().user().await.unwrap();
+    let author: UserProfile = crab.get("/user", None::<&()>).await.unwrap();
     AccountInfo {
+        identity: CommitIdentity {
+            name: author.name.clone().filter(|name| !name.is_empty()).unwrap_or(author.login.clone()),
+            email: author.email.clone().unwrap_or(noreply_email(author.id, &author.login))
+        },
         name: author.login,
         avatar: get_image(author.avatar_url).await.unwrap(),
         count,
@@ -137,6 +209,32 @@ pub async fn get_account_info(crab: Octocrab, count: usize) -> AccountInfo {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CollaboratorInfo {
+    pub login: String,
+    pub id: u64
+}
+
+impl std::fmt::Display for CollaboratorInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.login)
+    }
+}
+
+/// Lists the collaborators of a repository, falling back to its contributors when the account is
+/// not allowed to see its collaborators.
+pub async fn get_collaborators(crab: Octocrab, owner: String, project_name: String) -> Vec<CollaboratorInfo> {
+    let parameters = serde_json::json!({ "per_page": 100 });
+    let route = format!("/repos/{}/{}/collaborators", owner, project_name);
+    match crab.get(route, Some(&parameters)).await {
+        Ok(collaborators) => collaborators,
+        Err(_) => {
+            let route = format!("/repos/{}/{}/contributors", owner, project_name);
+            crab.get(route, Some(&parameters)).await.unwrap_or(vec![])
+        }
+    }
+}
+
 pub async fn get_forked_repositories(crab: &Octocrab) -> Vec<Repository> {
     crab.current()
         .list_repos_for_authenticated_user()
@@ -226,9 +324,9 @@ pub async fn create_empty_commit(crab: &Octocrab, owner: &str, project_name: &st
     }
 }
 
-pub async fn push_commit(crab: &Octocrab, owner: &str, project_name: &str, workspace_id: &str, branch_sha: &str, commit: &str) -> Option<CommitObject> {
+pub async fn push_commit(crab: &Octocrab, owner: &str, project_name: &str, workspace_id: &str, branch_sha: &str, commit: &str) -> octocrab::Result<()> {
     let route = format!("/repos/{}/{}/git/refs/heads/{}", owner, project_name, workspace_id);
-    crab.post(route, Some(&serde_json::json!({ "ref": branch_sha, "sha": commit, "force": true }))).await.ok()
+    crab.post::<_, serde_json::Value>(route, Some(&serde_json::json!({ "ref": branch_sha, "sha": commit, "force": true }))).await.map(|_| ())
 }
 
 pub async fn is_private(crab: &Octocrab, source_owner: &str, source_name: &str) -> bool {
@@ -252,14 +350,20 @@ pub async fn create_draft_pull_request(crab: &Octocrab, source_owner: &str, sour
 pub struct TreeObject {
     pub sha: String,
     pub url: String,
-    pub tree: Vec<TreePart>
+    pub tree: Vec<TreePart>,
+    #[serde(default)]
+    pub truncated: bool
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct TreePart {
     pub sha: String,
     pub url: String,
-    pub path: String
+    pub path: String,
+    pub mode: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub size: Option<u64>
 }
 
 pub async fn get_repository_content(crab: &Octocrab, owner: &str, project_name: &str, branch: &str) -> TreeObject {
@@ -267,16 +371,23 @@ pub async fn get_repository_content(crab: &Octocrab, owner: &str, project_name:
     crab.get(route, Some(&serde_json::json!({ "recursive": true }))).await.unwrap()
 }
 
+/// Fetches a single level of a tree, as GitHub considers any value of its recursive parameter
+/// as a request for the whole tree.
+pub async fn get_tree_level(crab: &Octocrab, owner: &str, project_name: &str, tree_ish: &str) -> TreeObject {
+    let route = format!("/repos/{}/{}/git/trees/{}", owner, project_name, tree_ish);
+    crab.get(route, None::<&()>).await.unwrap()
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlobObject {
     pub content: String
 }
 
-pub async fn get_decoded_blob(crab: &Octocrab, owner: &str, project_name: &str, file_sha: &str) -> Result<Vec<u8>, DecodeError> {
+pub async fn get_decoded_blob(crab: &Octocrab, owner: &str, project_name: &str, file_sha: &str) -> Result<Vec<u8>, String> {
     let route = format!("/repos/{}/{}/git/blobs/{}", owner, project_name, file_sha);
-    let blob: BlobObject = crab.get(route, Some(&serde_json::json!({}))).await.unwrap();
+    let blob: BlobObject = crab.get(route, Some(&serde_json::json!({}))).await.map_err(|error| error.to_string())?;
     let content = blob.content.as_bytes().to_vec().into_iter().filter(|b| !b" \n\t\r\x0b\x0c".contains(b)).collect::<Vec<u8>>();
-    base64::prelude::BASE64_STANDARD.decode(content)
+    base64::prelude::BASE64_STANDARD.decode(content).map_err(|error| error.to_string())
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -285,12 +396,12 @@ pub struct BlobCreationResult {
     pub sha: String
 }
 
-pub async fn create_blob(crab: &Octocrab, owner: &str, project_name: &str, content: Vec<u8>) -> BlobCreationResult {
+pub async fn create_blob(crab: &Octocrab, owner: &str, project_name: &str, content: Vec<u8>) -> octocrab::Result<BlobCreationResult> {
     let route = format!("/repos/{}/{}/git/blobs", owner, project_name);
     crab.post(route, Some(&serde_json::json!({
         "content": base64::prelude::BASE64_STANDARD.encode(content),
         "encoding": "base64"
-    }))).await.unwrap()
+    }))).await
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -302,26 +413,39 @@ pub struct TreeCreationPart {
     pub sha: Option<String>
 }
 
-pub async fn create_tree(crab: &Octocrab, owner: &str, project_name: &str, workspace_id: &str, blobs: Vec<TreeCreationPart>) -> Option<(String, TreeObject)> {
-    match crab.repos(owner, project_name).get_ref(&Reference::Branch(workspace_id.to_string())).await.unwrap().object {
+/// Creates a tree on top of the one of the last commit of the workspace branch, returning that
+/// commit along with the tree, or None when the branch does not point to any commit.
+pub async fn create_tree(crab: &Octocrab, owner: &str, project_name: &str, workspace_id: &str, blobs: Vec<TreeCreationPart>) -> octocrab::Result<Option<(String, TreeObject)>> {
+    match crab.repos(owner, project_name).get_ref(&Reference::Branch(workspace_id.to_string())).await?.object {
         Object::Commit { sha, .. } => {
-            let page = crab.repos(owner, project_name).list_commits().sha(sha).per_page(1).send().await.unwrap();
-            let commit = page.items.last()?;
+            let page = crab.repos(owner, project_name).list_commits().sha(sha).per_page(1).send().await?;
+            let Some(commit) = page.items.last() else { return Ok(None) };
             let new_sha = &commit.sha;
             let tree_base = &commit.commit.tree.sha;
             let route = format!("/repos/{}/{}/git/trees", owner, project_name);
-            Some((new_sha.to_string(), crab.post(route, Some(&serde_json::json!({ "base_tree": tree_base.to_string(), "tree": blobs }))).await.unwrap()))
+            Ok(Some((new_sha.to_string(), crab.post(route, Some(&serde_json::json!({ "base_tree": tree_base.to_string(), "tree": blobs }))).await?)))
         }
-        _ => None
+        _ => Ok(None)
     }
 }
 
-pub async fn create_commit(crab: &Octocrab, owner: &str, project_name: &str, modification_name: &str, parent_sha: &str, tree_sha: &str) -> String {
-    crab.repos(owner, project_name)
-        .create_git_commit_object(modification_name, tree_sha)
-        .parents(vec![parent_sha.to_string()])
-        .send()
-        .await
-        .unwrap()
-        .sha
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitCreationResult {
+    pub sha: String
+}
+
+/// Creates a commit, leaving its author to GitHub when none is provided, which then records the
+/// account the token belongs to.
+pub async fn create_commit(crab: &Octocrab, owner: &str, project_name: &str, commit_message: &str, parent_sha: &str, tree_sha: &str, author: Option<&CommitIdentity>) -> octocrab::Result<String> {
+    let route = format!("/repos/{}/{}/git/commits", owner, project_name);
+    let mut body = serde_json::json!({
+        "message": commit_message,
+        "tree": tree_sha,
+        "parents": [parent_sha]
+    });
+    if let Some(author) = author {
+        body["author"] = serde_json::json!(author);
+    }
+    let result: CommitCreationResult = crab.post(route, Some(&body)).await?;
+    Ok(result.sha)
 }